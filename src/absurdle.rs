@@ -0,0 +1,109 @@
+//! Adversarial host.
+//!
+//! Hosts a game the way Absurdle does: no answer is fixed up front. After each guess, the host
+//! groups the answers that are still possible by the feedback the guess would produce, and answers
+//! with the feedback that keeps the largest group alive. The game is won once the guess is the only
+//! answer left.
+
+// Standard library imports
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+// Local crate imports
+use crate::pattern::Pattern;
+use crate::rules::FeedbackRules;
+use crate::rules::NytRules;
+use crate::GuessResult;
+use crate::LetterState;
+use crate::Library;
+
+/// Game host that delays choosing an answer for as long as possible
+pub struct AdversarialHost<'a> {
+    rules: Arc<dyn FeedbackRules>,
+    candidates: Vec<&'a str>,
+    history: Vec<GuessResult>,
+}
+
+impl<'a> AdversarialHost<'a> {
+
+    /// Create a host that may end up with any answer in the library, scoring with the New York Times rules
+    pub fn new(library: &'a Library) -> AdversarialHost<'a> {
+        AdversarialHost {
+            rules: Arc::new(NytRules),
+            candidates: library.answers.iter().map(|w| w.as_str()).collect(),
+            history: Vec::new(),
+        }
+    }
+
+    /// Score guesses with a different rule set
+    pub fn with_rules(self, rules: Arc<dyn FeedbackRules>) -> AdversarialHost<'a> {
+        AdversarialHost { rules, ..self }
+    }
+
+    /// Give feedback for a guess, keeping as many answers possible as the feedback allows.
+    /// Ties between equally large groups go to the feedback that reveals least, i.e. the lowest pattern,
+    /// so the host always answers the same way.
+    pub fn respond(&mut self, guess: &str) -> &GuessResult {
+        let mut buckets: BTreeMap<Pattern, Vec<&'a str>> = BTreeMap::new();
+        for &answer in &self.candidates {
+            let result: GuessResult = GuessResult::evaluate_guess_with(self.rules.as_ref(), guess, answer);
+            buckets.entry(result.pattern()).or_default().push(answer);
+        }
+        // Iterating in reverse makes max_by_key keep the lowest pattern among equally large buckets
+        let (pattern, candidates): (Pattern, Vec<&'a str>) = buckets.into_iter().rev()
+            .max_by_key(|(_, bucket)| bucket.len())
+            .expect("Host must have candidates left");
+        self.candidates = candidates;
+        self.history.push(GuessResult::from_pattern(guess, pattern));
+        self.history.last().expect("Feedback was just recorded")
+    }
+
+    /// Answers the host could still settle on
+    pub fn candidates(&self) -> &[&'a str] {
+        &self.candidates
+    }
+
+    /// Feedback given so far
+    pub fn history(&self) -> &[GuessResult] {
+        &self.history
+    }
+
+    /// Whether the last guess was marked entirely correct
+    pub fn is_won(&self) -> bool {
+        self.history.last().is_some_and(|result| result.states().iter().all(|s| *s == LetterState::Correct))
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    // Local crate imports
+    use super::*;
+    use crate::tests::create_word_library;
+    use crate::tests::REPEATED_LETTER_WORDS;
+
+    #[test]
+    fn test_host_keeps_largest_group() {
+        let library: Library = create_word_library(&REPEATED_LETTER_WORDS, &[]);
+        let mut host: AdversarialHost = AdversarialHost::new(&library);
+        // plumb shares no letters with crane, trace, react, cater or eerie
+        assert_eq!(host.respond("plumb").pattern(), Pattern::from_code(0));
+        assert_eq!(host.candidates(), ["crane", "trace", "react", "cater", "eerie"]);
+        assert!(!host.is_won());
+    }
+
+    #[test]
+    fn test_host_breaks_ties_towards_less_feedback() {
+        let answers: Vec<String> = vec!["plumb".to_string(), "eerie".to_string()];
+        let library: Library = Library::new(answers.clone(), answers).unwrap();
+        // Both answers are alone in their group, so the host picks the group revealing least
+        let mut host: AdversarialHost = AdversarialHost::new(&library);
+        host.respond("plumb");
+        assert_eq!(host.candidates(), ["eerie"]);
+        assert!(!host.is_won());
+        host.respond("eerie");
+        assert!(host.is_won());
+    }
+
+}
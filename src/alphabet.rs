@@ -0,0 +1,129 @@
+//! Alphabets.
+//!
+//! Localized versions of the game use different letters. An alphabet lists the letters a word list
+//! may use, so that loading can reject words with stray characters. Letters are single chars in
+//! composed (NFC) form, e.g. 'ñ' rather than 'n' followed by a combining tilde.
+
+/// Set of letters that words may contain
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "AlphabetLetters"))]
+pub struct Alphabet {
+    name: String,
+    letters: Vec<char>,
+}
+
+/// Serialized form of an alphabet, whose letters are sorted again when deserialized
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct AlphabetLetters {
+    name: String,
+    letters: Vec<char>,
+}
+
+#[cfg(feature = "serde")]
+impl From<AlphabetLetters> for Alphabet {
+
+    fn from(alphabet: AlphabetLetters) -> Alphabet {
+        Alphabet::new(&alphabet.name, &alphabet.letters.iter().collect::<String>())
+    }
+
+}
+
+impl Alphabet {
+
+    /// Create an alphabet from its letters
+    pub fn new(name: &str, letters: &str) -> Alphabet {
+        let mut letters: Vec<char> = letters.chars().collect();
+        letters.sort_unstable();
+        letters.dedup();
+        Alphabet { name: name.to_string(), letters }
+    }
+
+    /// The 26 letters of the English alphabet
+    pub fn english() -> Alphabet {
+        Alphabet::new("en", "abcdefghijklmnopqrstuvwxyz")
+    }
+
+    /// The English letters plus ñ
+    pub fn spanish() -> Alphabet {
+        Alphabet::new("es", "abcdefghijklmnopqrstuvwxyzñ")
+    }
+
+    /// The English letters plus the umlauts and ß
+    pub fn german() -> Alphabet {
+        Alphabet::new("de", "abcdefghijklmnopqrstuvwxyzäöüß")
+    }
+
+    /// The 33 letters of the Russian Cyrillic alphabet
+    pub fn russian() -> Alphabet {
+        Alphabet::new("ru", "абвгдеёжзийклмнопрстуфхцчшщъыьэюя")
+    }
+
+    /// Look up a built-in alphabet by its language code: en, es, de or ru
+    pub fn by_name(name: &str) -> Option<Alphabet> {
+        match name {
+            "en" => Some(Alphabet::english()),
+            "es" => Some(Alphabet::spanish()),
+            "de" => Some(Alphabet::german()),
+            "ru" => Some(Alphabet::russian()),
+            _ => None,
+        }
+    }
+
+    /// Name of the alphabet
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Letters of the alphabet, in sorted order
+    pub fn letters(&self) -> &[char] {
+        &self.letters
+    }
+
+    /// Whether a letter is in the alphabet
+    pub fn contains(&self, letter: char) -> bool {
+        self.letters.binary_search(&letter).is_ok()
+    }
+
+    /// First character of a word that is not in the alphabet, if any
+    pub fn invalid_letter(&self, word: &str) -> Option<char> {
+        word.chars().find(|&c| !self.contains(c))
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    // Local crate imports
+    use super::*;
+
+    #[test]
+    fn test_built_in_alphabets() {
+        assert_eq!(Alphabet::english().letters().len(), 26);
+        assert_eq!(Alphabet::spanish().invalid_letter("señal"), None);
+        assert_eq!(Alphabet::english().invalid_letter("señal"), Some('ñ'));
+        assert_eq!(Alphabet::german().invalid_letter("größe"), None);
+        assert_eq!(Alphabet::russian().letters().len(), 33);
+        assert_eq!(Alphabet::russian().invalid_letter("ёжики"), None);
+        assert_eq!(Alphabet::russian().invalid_letter("crane"), Some('c'));
+        assert_eq!(Alphabet::by_name("de"), Some(Alphabet::german()));
+        assert_eq!(Alphabet::by_name("xx"), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialized_letters_are_sorted() {
+        let alphabet: Alphabet = serde_json::from_str(r#"{"name": "abc", "letters": ["c", "a", "b", "a"]}"#).unwrap();
+        assert_eq!(alphabet, Alphabet::new("abc", "abc"));
+        assert!(alphabet.contains('c'));
+    }
+
+    #[test]
+    fn test_decomposed_letters_are_not_in_alphabet() {
+        // 'n' followed by a combining tilde is two chars, neither of which is 'ñ'
+        assert_eq!(Alphabet::spanish().invalid_letter("sen\u{303}al"), Some('\u{303}'));
+    }
+
+}
@@ -0,0 +1,131 @@
+//! Feedback parsing.
+//!
+//! Reads the colors shown by a game into letter states, so that a guess result can be recorded
+//! without knowing the answer. Each letter can be written as an emoji square, a letter code or a
+//! digit, and the forms can be mixed:
+//!
+//! | State   | Emoji        | Letters                | Digit |
+//! |---------|--------------|------------------------|-------|
+//! | Correct | 🟩 🟧        | G g                    | 2     |
+//! | Present | 🟨 🟦        | Y y                    | 1     |
+//! | Absent  | ⬛ ⬜ 🟥     | B b X x . - _          | 0     |
+//!
+//! The orange and blue squares are the high contrast colors. Emoji variation selectors are ignored.
+
+// Standard library imports
+use std::error::Error;
+use std::fmt;
+
+// Local crate imports
+use crate::pattern::MAX_PATTERN_LENGTH;
+use crate::GuessResult;
+use crate::LetterState;
+
+/// Emoji variation selector, which some platforms append to the square emoji
+const VARIATION_SELECTOR: char = '\u{fe0f}';
+
+/// Why feedback could not be read
+#[derive(Clone, Debug, PartialEq)]
+pub enum FeedbackError {
+
+    /// The feedback contains no letters
+    Empty,
+
+    /// A character is not a known way of writing a letter state. The position is zero-based.
+    InvalidCharacter { character: char, position: usize },
+
+    /// The feedback does not have one state per letter of the guess
+    WrongLength { expected: usize, found: usize },
+
+    /// The guess has more letters than feedback patterns support
+    TooLong { found: usize, max: usize },
+}
+
+impl fmt::Display for FeedbackError {
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeedbackError::Empty => write!(f, "Feedback is empty"),
+            FeedbackError::InvalidCharacter { character, position } => {
+                write!(f, "{:?} at position {} is not a color, letter code or digit", character, position + 1)
+            },
+            FeedbackError::WrongLength { expected, found } => {
+                write!(f, "Feedback has {} letters, expected {}", found, expected)
+            },
+            FeedbackError::TooLong { found, max } => {
+                write!(f, "Guess has {} letters, but at most {} are supported", found, max)
+            },
+        }
+    }
+
+}
+
+impl Error for FeedbackError {}
+
+/// Read feedback into a sequence of letter states
+pub fn parse_feedback(feedback: &str) -> Result<Vec<LetterState>, FeedbackError> {
+    let states: Vec<LetterState> = feedback.chars()
+        .filter(|&c| c != VARIATION_SELECTOR)
+        .enumerate()
+        .map(|(position, character)| match character {
+            'G' | 'g' | '2' | '🟩' | '🟧' => Ok(LetterState::Correct),
+            'Y' | 'y' | '1' | '🟨' | '🟦' => Ok(LetterState::Present),
+            'B' | 'b' | 'X' | 'x' | '.' | '-' | '_' | '0' | '⬛' | '⬜' | '🟥' => Ok(LetterState::Absent),
+            _ => Err(FeedbackError::InvalidCharacter { character, position }),
+        })
+        .collect::<Result<_, _>>()?;
+    if states.is_empty() {
+        return Err(FeedbackError::Empty);
+    }
+    Ok(states)
+}
+
+impl GuessResult {
+
+    /// Create a result for a guess from the feedback a game showed for it
+    pub fn from_feedback(guess: &str, feedback: &str) -> Result<GuessResult, FeedbackError> {
+        let states: Vec<LetterState> = parse_feedback(feedback)?;
+        let expected: usize = guess.chars().count();
+        if states.len() != expected {
+            return Err(FeedbackError::WrongLength { expected, found: states.len() });
+        }
+        if expected > MAX_PATTERN_LENGTH {
+            return Err(FeedbackError::TooLong { found: expected, max: MAX_PATTERN_LENGTH });
+        }
+        Ok(GuessResult::from_states(guess, states))
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    // Local crate imports
+    use super::*;
+    use crate::pattern::Pattern;
+
+    #[test]
+    fn test_feedback_forms_agree() {
+        let expected: Pattern = GuessResult::evaluate_guess("crane", "caper").pattern();
+        for feedback in ["GYYBY", "gyy.y", "GYY-Y", "21101", "🟩🟨🟨⬛🟨", "🟩🟨🟨⬜🟨", "🟩🟨🟨🟥🟨", "🟧🟦🟦⬛\u{fe0f}🟦"] {
+            let result: GuessResult = GuessResult::from_feedback("crane", feedback).expect("Feedback should parse");
+            assert_eq!(result.pattern(), expected, "Wrong states for {:?}", feedback);
+        }
+    }
+
+    #[test]
+    fn test_invalid_feedback() {
+        assert_eq!(parse_feedback("").err(), Some(FeedbackError::Empty));
+        let error: Option<FeedbackError> = parse_feedback("GYZBB").err();
+        assert_eq!(error, Some(FeedbackError::InvalidCharacter { character: 'Z', position: 2 }));
+        assert_eq!(
+            GuessResult::from_feedback("crane", "GYB").err(),
+            Some(FeedbackError::WrongLength { expected: 5, found: 3 })
+        );
+        assert_eq!(
+            GuessResult::from_feedback(&"a".repeat(21), &"B".repeat(21)).err(),
+            Some(FeedbackError::TooLong { found: 21, max: MAX_PATTERN_LENGTH })
+        );
+    }
+
+}
@@ -0,0 +1,165 @@
+//! Candidate filtering.
+//!
+//! Narrows a list of words down to those that are consistent with a history of guess results.
+
+// Standard library imports
+use std::sync::Arc;
+
+// Local crate imports
+use crate::rules::FeedbackRules;
+use crate::rules::NytRules;
+use crate::GuessResult;
+use crate::Library;
+
+/// Accumulated guess results used to decide which words could still be the answer
+pub struct CandidateFilter {
+    rules: Arc<dyn FeedbackRules>,
+    history: Vec<GuessResult>,
+}
+
+impl Default for CandidateFilter {
+
+    fn default() -> CandidateFilter {
+        CandidateFilter::new()
+    }
+
+}
+
+impl CandidateFilter {
+
+    /// Create a filter with no guess results, using the New York Times rules
+    pub fn new() -> CandidateFilter {
+        CandidateFilter::from_history(Vec::new())
+    }
+
+    /// Create a filter from an existing history of guess results
+    pub fn from_history(history: Vec<GuessResult>) -> CandidateFilter {
+        CandidateFilter { rules: Arc::new(NytRules), history }
+    }
+
+    /// Use a different rule set to decide whether a word matches a guess result
+    pub fn with_rules(self, rules: Arc<dyn FeedbackRules>) -> CandidateFilter {
+        CandidateFilter { rules, ..self }
+    }
+
+    /// Add a guess result to the filter
+    pub fn add(&mut self, result: GuessResult) {
+        self.history.push(result);
+    }
+
+    /// Guess results the filter has been built from, in the order they were added
+    pub fn history(&self) -> &[GuessResult] {
+        &self.history
+    }
+
+    /// Check whether a word is consistent with every guess result in the filter
+    pub fn is_candidate(&self, word: &str) -> bool {
+        self.history.iter().all(|result| matches_with(self.rules.as_ref(), word, result))
+    }
+
+    /// Keep only the words that are consistent with every guess result in the filter
+    pub fn filter<'a>(&self, words: &'a [String]) -> Vec<&'a str> {
+        words.iter().map(|w| w.as_str()).filter(|w| self.is_candidate(w)).collect()
+    }
+
+    /// Answers in the library that are consistent with every guess result in the filter
+    pub fn remaining_answers<'a>(&self, library: &'a Library) -> Vec<&'a str> {
+        self.filter(&library.answers)
+    }
+
+}
+
+impl Library {
+
+    /// Answers that are consistent with every guess result in the history
+    pub fn remaining_answers(&self, history: &[GuessResult]) -> Vec<&str> {
+        self.answers.iter().map(|w| w.as_str()).filter(|w| is_consistent(w, history)).collect()
+    }
+
+}
+
+/// Check whether a word could be the answer given a single guess result.
+/// A word is consistent if guessing the same word against it would produce the same feedback.
+pub fn matches(word: &str, result: &GuessResult) -> bool {
+    matches_with(&NytRules, word, result)
+}
+
+/// Check whether a word could be the answer given a single guess result scored with the given rules
+pub fn matches_with(rules: &dyn FeedbackRules, word: &str, result: &GuessResult) -> bool {
+    if word.chars().count() != result.guess.chars().count() {
+        return false;
+    }
+    GuessResult::evaluate_guess_with(rules, &result.guess, word).states == result.states
+}
+
+/// Check whether a word could be the answer given every guess result in a history
+pub fn is_consistent(word: &str, history: &[GuessResult]) -> bool {
+    history.iter().all(|result| matches(word, result))
+}
+
+/// Narrow an existing candidate list with one more guess result
+pub fn narrow<'a>(candidates: &[&'a str], result: &GuessResult) -> Vec<&'a str> {
+    narrow_with(&NytRules, candidates, result)
+}
+
+/// Narrow an existing candidate list with one more guess result scored with the given rules
+pub fn narrow_with<'a>(rules: &dyn FeedbackRules, candidates: &[&'a str], result: &GuessResult) -> Vec<&'a str> {
+    candidates.iter().copied().filter(|w| matches_with(rules, w, result)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    // Local crate imports
+    use super::*;
+    use crate::rules::NaiveRules;
+    use crate::tests::create_small_library;
+
+    #[test]
+    fn test_empty_history_keeps_all_answers() {
+        let library: Library = create_small_library();
+        assert_eq!(library.remaining_answers(&[]).len(), library.answers.len());
+    }
+
+    #[test]
+    fn test_answer_is_always_a_candidate() {
+        let library: Library = create_small_library();
+        for answer in &library.answers {
+            let history: Vec<GuessResult> = library.guesses.iter()
+                .map(|guess| GuessResult::evaluate_guess(guess, answer))
+                .collect();
+            let remaining: Vec<&str> = library.remaining_answers(&history);
+            assert_eq!(remaining, vec![answer.as_str()], "Expected only {} to remain", answer);
+        }
+    }
+
+    #[test]
+    fn test_filter_narrows_incrementally() {
+        let library: Library = create_small_library();
+        let mut filter: CandidateFilter = CandidateFilter::new();
+        filter.add(GuessResult::evaluate_guess("crane", "trace"));
+        let remaining: Vec<&str> = filter.remaining_answers(&library);
+        assert!(remaining.contains(&"trace"));
+        assert!(!remaining.contains(&"plumb"));
+        assert!(!remaining.contains(&"crane"));
+        let narrowed: Vec<&str> = narrow(&remaining, &GuessResult::evaluate_guess("react", "trace"));
+        assert_eq!(narrowed, vec!["trace"]);
+    }
+
+    #[test]
+    fn test_filter_uses_rules() {
+        let words: Vec<String> = vec!["ether".to_string(), "three".to_string()];
+        let result: GuessResult = GuessResult::evaluate_guess_with(&NaiveRules, "eerie", "ether");
+        let nyt: CandidateFilter = CandidateFilter::from_history(vec![result]);
+        assert!(nyt.filter(&words).is_empty());
+        let naive: CandidateFilter = nyt.with_rules(Arc::new(NaiveRules));
+        assert_eq!(naive.filter(&words), vec!["ether"]);
+    }
+
+    #[test]
+    fn test_words_of_different_length_are_rejected() {
+        let result: GuessResult = GuessResult::evaluate_guess("crane", "trace");
+        assert!(!matches("traces", &result));
+    }
+
+}
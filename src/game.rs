@@ -0,0 +1,262 @@
+//! Game sessions.
+//!
+//! A game owns the secret answer and applies the rules of play: guesses must be words from the
+//! library with the right number of letters, hard mode hints must be reused when it is on, and the
+//! game ends when the answer is guessed or the turn limit is reached.
+
+// Standard library imports
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+// Local crate imports
+use crate::hard_mode;
+use crate::hard_mode::HardModeViolation;
+use crate::rules::FeedbackRules;
+use crate::rules::NytRules;
+use crate::GuessResult;
+use crate::LetterState;
+use crate::Library;
+
+/// Where a game stands
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameStatus {
+
+    /// More guesses can be made
+    InProgress,
+
+    /// The answer was guessed in the given number of guesses
+    Won { turns: usize },
+
+    /// Every turn was used without guessing the answer
+    Lost,
+}
+
+/// Why a game could not be started
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameError {
+
+    /// The answer has the wrong number of letters
+    WrongLength { expected: usize, found: usize },
+
+    /// The answer is not one of the library's answers
+    NotAnAnswer { word: String },
+}
+
+impl fmt::Display for GameError {
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::WrongLength { expected, found } => {
+                write!(f, "The answer must have {} letters, got {}", expected, found)
+            },
+            GameError::NotAnAnswer { word } => write!(f, "{} is not in the answer list", word),
+        }
+    }
+
+}
+
+impl Error for GameError {}
+
+/// Why a guess was not accepted. A rejected guess does not use up a turn.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GuessError {
+
+    /// The game has already been won or lost
+    GameOver,
+
+    /// The guess has the wrong number of letters
+    WrongLength { expected: usize, found: usize },
+
+    /// The guess is not one of the library's guesses
+    NotInWordList { word: String },
+
+    /// The guess ignores a hint, and the game is in hard mode
+    HardMode(HardModeViolation),
+}
+
+impl fmt::Display for GuessError {
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GuessError::GameOver => write!(f, "The game is over"),
+            GuessError::WrongLength { expected, found } => {
+                write!(f, "Guesses must have {} letters, got {}", expected, found)
+            },
+            GuessError::NotInWordList { word } => write!(f, "{} is not in the word list", word),
+            GuessError::HardMode(violation) => write!(f, "{}", violation),
+        }
+    }
+
+}
+
+impl Error for GuessError {
+
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GuessError::HardMode(violation) => Some(violation),
+            _ => None,
+        }
+    }
+
+}
+
+/// A game against a secret answer
+pub struct Game<'a> {
+    library: &'a Library,
+    answer: String,
+    rules: Arc<dyn FeedbackRules>,
+    max_turns: usize,
+    hard_mode: bool,
+    history: Vec<GuessResult>,
+}
+
+impl<'a> Game<'a> {
+
+    /// Start a game with six turns against one of the library's answers, scored with the New York Times rules
+    pub fn new(library: &'a Library, answer: &str) -> Result<Game<'a>, GameError> {
+        let length: usize = answer.chars().count();
+        if length != library.word_length {
+            return Err(GameError::WrongLength { expected: library.word_length, found: length });
+        }
+        if !library.is_answer(answer) {
+            return Err(GameError::NotAnAnswer { word: answer.to_string() });
+        }
+        Ok(Game {
+            library,
+            answer: answer.to_string(),
+            rules: Arc::new(NytRules),
+            max_turns: 6,
+            hard_mode: false,
+            history: Vec::new(),
+        })
+    }
+
+    /// Allow a different number of guesses
+    pub fn with_max_turns(self, max_turns: usize) -> Game<'a> {
+        Game { max_turns, ..self }
+    }
+
+    /// Score guesses with a different rule set
+    pub fn with_rules(self, rules: Arc<dyn FeedbackRules>) -> Game<'a> {
+        Game { rules, ..self }
+    }
+
+    /// Require every guess to use the hints revealed so far
+    pub fn with_hard_mode(self, hard_mode: bool) -> Game<'a> {
+        Game { hard_mode, ..self }
+    }
+
+    /// Check that a guess would be accepted, without playing it
+    pub fn check_guess(&self, guess: &str) -> Result<(), GuessError> {
+        if self.status() != GameStatus::InProgress {
+            return Err(GuessError::GameOver);
+        }
+        let length: usize = guess.chars().count();
+        if length != self.library.word_length {
+            return Err(GuessError::WrongLength { expected: self.library.word_length, found: length });
+        }
+        if !self.library.is_guess(guess) {
+            return Err(GuessError::NotInWordList { word: guess.to_string() });
+        }
+        if self.hard_mode {
+            hard_mode::check_hard_mode(guess, &self.history).map_err(GuessError::HardMode)?;
+        }
+        Ok(())
+    }
+
+    /// Play a guess, returning its feedback
+    pub fn guess(&mut self, guess: &str) -> Result<&GuessResult, GuessError> {
+        self.check_guess(guess)?;
+        self.history.push(GuessResult::evaluate_guess_with(self.rules.as_ref(), guess, &self.answer));
+        Ok(self.history.last().expect("Guess was just recorded"))
+    }
+
+    /// Whether the game is still going, won or lost
+    pub fn status(&self) -> GameStatus {
+        let solved: bool = self.history.last()
+            .is_some_and(|result| result.states().iter().all(|state| *state == LetterState::Correct));
+        if solved {
+            GameStatus::Won { turns: self.history.len() }
+        } else if self.history.len() >= self.max_turns {
+            GameStatus::Lost
+        } else {
+            GameStatus::InProgress
+        }
+    }
+
+    /// The secret answer
+    pub fn answer(&self) -> &str {
+        &self.answer
+    }
+
+    /// Feedback for every guess played so far
+    pub fn history(&self) -> &[GuessResult] {
+        &self.history
+    }
+
+    /// Number of guesses allowed
+    pub fn max_turns(&self) -> usize {
+        self.max_turns
+    }
+
+    /// Number of guesses still allowed
+    pub fn turns_left(&self) -> usize {
+        self.max_turns.saturating_sub(self.history.len())
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    // Local crate imports
+    use super::*;
+    use crate::tests::create_word_library;
+
+    /// Answers of the library the tests use
+    const ANSWERS: [&str; 4] = ["crane", "slate", "trace", "plumb"];
+
+    #[test]
+    fn test_game_is_won_by_guessing_the_answer() {
+        let library: Library = create_word_library(&ANSWERS, &[]);
+        let mut game: Game = Game::new(&library, "trace").unwrap();
+        assert_eq!(game.guess("crane").unwrap().pattern(), GuessResult::evaluate_guess("crane", "trace").pattern());
+        assert_eq!(game.status(), GameStatus::InProgress);
+        game.guess("trace").unwrap();
+        assert_eq!(game.status(), GameStatus::Won { turns: 2 });
+        assert_eq!(game.guess("slate").err(), Some(GuessError::GameOver));
+    }
+
+    #[test]
+    fn test_game_is_lost_after_the_turn_limit() {
+        let library: Library = create_word_library(&ANSWERS, &[]);
+        let mut game: Game = Game::new(&library, "plumb").unwrap().with_max_turns(2);
+        game.guess("crane").unwrap();
+        assert_eq!(game.turns_left(), 1);
+        game.guess("slate").unwrap();
+        assert_eq!(game.status(), GameStatus::Lost);
+        assert_eq!(game.guess("plumb").err(), Some(GuessError::GameOver));
+    }
+
+    #[test]
+    fn test_invalid_answers_are_rejected() {
+        let library: Library = create_word_library(&ANSWERS, &[]);
+        assert_eq!(Game::new(&library, "cranes").err(), Some(GameError::WrongLength { expected: 5, found: 6 }));
+        assert_eq!(Game::new(&library, "zzzzz").err(), Some(GameError::NotAnAnswer { word: "zzzzz".to_string() }));
+    }
+
+    #[test]
+    fn test_invalid_guesses_are_rejected_without_using_a_turn() {
+        let library: Library = create_word_library(&ANSWERS, &[]);
+        let mut game: Game = Game::new(&library, "trace").unwrap().with_hard_mode(true);
+        assert_eq!(game.guess("cranes").err(), Some(GuessError::WrongLength { expected: 5, found: 6 }));
+        assert_eq!(game.check_guess("zzzzz"), Err(GuessError::NotInWordList { word: "zzzzz".to_string() }));
+        assert_eq!(game.check_guess("zzzzz").unwrap_err().to_string(), "zzzzz is not in the word list");
+        game.guess("crane").unwrap();
+        assert!(matches!(game.guess("plumb"), Err(GuessError::HardMode(_))));
+        assert_eq!(game.history().len(), 1);
+    }
+
+}
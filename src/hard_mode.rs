@@ -0,0 +1,131 @@
+//! Hard mode rules.
+//!
+//! In hard mode, every hint revealed by earlier guesses must be used in later guesses: letters marked
+//! Correct must stay in place, and letters marked Present must appear somewhere in the guess.
+
+// Standard library imports
+use std::error::Error;
+use std::fmt;
+
+// Local crate imports
+use crate::GuessResult;
+use crate::LetterState;
+
+/// Why a guess is not allowed in hard mode
+#[derive(Clone, Debug, PartialEq)]
+pub enum HardModeViolation {
+
+    /// A letter revealed as Correct is not in the same position. The position is zero-based.
+    MissingCorrect { position: usize, letter: char },
+
+    /// A letter revealed as Correct or Present appears fewer times than the hints require
+    MissingPresent { letter: char, required: usize, found: usize },
+}
+
+impl fmt::Display for HardModeViolation {
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HardModeViolation::MissingCorrect { position, letter } => {
+                write!(f, "{} letter must be {}", ordinal(position + 1), letter.to_uppercase())
+            },
+            HardModeViolation::MissingPresent { letter, required: 1, .. } => {
+                write!(f, "Guess must contain {}", letter.to_uppercase())
+            },
+            HardModeViolation::MissingPresent { letter, required, .. } => {
+                write!(f, "Guess must contain {} {}s", required, letter.to_uppercase())
+            },
+        }
+    }
+
+}
+
+impl Error for HardModeViolation {}
+
+/// Check that a guess uses every hint revealed by the earlier guess results
+pub fn check_hard_mode(guess: &str, history: &[GuessResult]) -> Result<(), HardModeViolation> {
+    let guess_chars: Vec<char> = guess.chars().collect();
+    for result in history {
+        for (position, (letter, state)) in result.guess.chars().zip(&result.states).enumerate() {
+            if *state == LetterState::Correct && guess_chars.get(position) != Some(&letter) {
+                return Err(HardModeViolation::MissingCorrect { position, letter });
+            }
+        }
+    }
+    for result in history {
+        let revealed: Vec<char> = result.guess.chars().zip(&result.states)
+            .filter(|(_, state)| **state != LetterState::Absent)
+            .map(|(letter, _)| letter)
+            .collect();
+        for &letter in &revealed {
+            let required: usize = revealed.iter().filter(|&&c| c == letter).count();
+            let found: usize = guess_chars.iter().filter(|&&c| c == letter).count();
+            if found < required {
+                return Err(HardModeViolation::MissingPresent { letter, required, found });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Keep only the guesses that are allowed in hard mode after the given guess results
+pub fn hard_mode_guesses<'a>(guesses: &[&'a str], history: &[GuessResult]) -> Vec<&'a str> {
+    guesses.iter().copied().filter(|guess| check_hard_mode(guess, history).is_ok()).collect()
+}
+
+/// English ordinal for a positive number, e.g. 1st, 2nd, 3rd, 4th
+fn ordinal(n: usize) -> String {
+    let suffix: &str = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+
+    // Local crate imports
+    use super::*;
+
+    #[test]
+    fn test_hints_must_be_reused() {
+        // crane against trace: C present, R correct, A correct, N absent, E correct
+        let history: Vec<GuessResult> = vec![GuessResult::evaluate_guess("crane", "trace")];
+        assert_eq!(check_hard_mode("trace", &history), Ok(()));
+        assert_eq!(check_hard_mode("grape", &history), Err(HardModeViolation::MissingPresent {
+            letter: 'c', required: 1, found: 0,
+        }));
+        assert_eq!(check_hard_mode("cramp", &history), Err(HardModeViolation::MissingCorrect {
+            position: 4, letter: 'e',
+        }));
+        assert_eq!(
+            check_hard_mode("cramp", &history).unwrap_err().to_string(),
+            "5th letter must be E"
+        );
+    }
+
+    #[test]
+    fn test_repeated_hints_need_repeated_letters() {
+        // eerie against ether: E correct, E present, R present
+        let history: Vec<GuessResult> = vec![GuessResult::evaluate_guess("eerie", "ether")];
+        assert_eq!(check_hard_mode("ether", &history), Ok(()));
+        assert_eq!(check_hard_mode("error", &history), Err(HardModeViolation::MissingPresent {
+            letter: 'e', required: 2, found: 1,
+        }));
+        assert_eq!(check_hard_mode("error", &history).unwrap_err().to_string(), "Guess must contain 2 Es");
+    }
+
+    #[test]
+    fn test_hard_mode_guesses_keep_candidates() {
+        let words: [&str; 5] = ["crane", "trace", "brace", "grace", "plumb"];
+        let history: Vec<GuessResult> = vec![GuessResult::evaluate_guess("plumb", "grace")];
+        assert_eq!(hard_mode_guesses(&words, &history), words.to_vec());
+        let history: Vec<GuessResult> = vec![GuessResult::evaluate_guess("crane", "grace")];
+        assert_eq!(hard_mode_guesses(&words, &history), vec!["crane", "trace", "brace", "grace"]);
+    }
+
+}
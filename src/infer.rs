@@ -0,0 +1,142 @@
+//! Reverse inference.
+//!
+//! A share grid shows the feedback for each guess but not the guesses. Given a possible answer, a
+//! guess could have produced a row if evaluating it against that answer gives the row's pattern, so
+//! each row can be explained by a set of words from the library. When the answer is not known, every
+//! answer that could explain the whole grid is tried.
+//!
+//! Rows are explained independently: in hard mode, a guess is not checked against the hints revealed
+//! by the guesses before it, since those are not known either.
+
+// Standard library imports
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+// Local crate imports
+use crate::pattern::Pattern;
+use crate::rules::FeedbackRules;
+use crate::rules::NytRules;
+use crate::share::ShareGrid;
+use crate::Library;
+
+/// Words that could have produced each row of a grid, for one answer
+#[derive(Clone, Debug, PartialEq)]
+pub struct Explanation<'a> {
+
+    /// The answer the grid was played against
+    pub answer: String,
+
+    /// Guesses from the library that produce each row's feedback against the answer
+    pub rows: Vec<Vec<&'a str>>,
+}
+
+/// Explains share grids with the words of a library
+pub struct GridInference<'a> {
+    library: &'a Library,
+    rules: Arc<dyn FeedbackRules>,
+}
+
+impl<'a> GridInference<'a> {
+
+    /// Create an inference over a library's guesses, scoring with the New York Times rules
+    pub fn new(library: &'a Library) -> GridInference<'a> {
+        GridInference { library, rules: Arc::new(NytRules) }
+    }
+
+    /// Score guesses with a different rule set
+    pub fn with_rules(self, rules: Arc<dyn FeedbackRules>) -> GridInference<'a> {
+        GridInference { rules, ..self }
+    }
+
+    /// Explain a grid against a known answer, which need not be in the library.
+    /// Returns None if some row cannot be produced by any guess.
+    pub fn explain_with_answer(&self, grid: &ShareGrid, answer: &str) -> Option<Explanation<'a>> {
+        if grid.word_length != self.library.word_length || answer.chars().count() != grid.word_length {
+            return None;
+        }
+        let mut rows: Vec<Vec<&'a str>> = vec![Vec::new(); grid.rows.len()];
+        for guess in &self.library.guesses {
+            let pattern: Pattern = Pattern::evaluate_with(self.rules.as_ref(), guess, answer);
+            for (row, guesses) in grid.rows.iter().zip(rows.iter_mut()) {
+                if *row == pattern {
+                    guesses.push(guess);
+                }
+            }
+        }
+        if rows.iter().any(|guesses| guesses.is_empty()) {
+            return None;
+        }
+        Some(Explanation { answer: answer.to_string(), rows })
+    }
+
+    /// Explain a grid against every answer in the library that could have produced it, in library order
+    pub fn explain(&self, grid: &ShareGrid) -> Vec<Explanation<'a>> {
+        self.library.answers.iter()
+            .filter_map(|answer| self.explain_with_answer(grid, answer))
+            .collect()
+    }
+
+    /// Guesses that could have opened a grid, over every explanation, in sorted order
+    pub fn openers(explanations: &[Explanation<'a>]) -> Vec<&'a str> {
+        let openers: BTreeSet<&'a str> = explanations.iter()
+            .filter_map(|explanation| explanation.rows.first())
+            .flatten()
+            .copied()
+            .collect();
+        openers.into_iter().collect()
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    // Local crate imports
+    use super::*;
+    use crate::tests::create_word_library;
+    use crate::GuessResult;
+
+    /// Answers of the library the tests use
+    const ANSWERS: [&str; 8] = ["caper", "paper", "crane", "trace", "react", "cover", "plumb", "eerie"];
+
+    /// Words the tests can guess that are never answers
+    const GUESS_ONLY: [&str; 3] = ["slate", "stale", "zzzzz"];
+
+    /// Grid of a game against "caper" solved in four guesses
+    fn create_grid_fixture() -> ShareGrid {
+        let results: Vec<GuessResult> = ["slate", "crane", "cover", "caper"].iter()
+            .map(|guess| GuessResult::evaluate_guess(guess, "caper"))
+            .collect();
+        ShareGrid::from_results(1, &results, 6, false)
+    }
+
+    #[test]
+    fn test_rows_are_explained_by_matching_guesses() {
+        let library: Library = create_word_library(&ANSWERS, &GUESS_ONLY);
+        let inference: GridInference = GridInference::new(&library);
+        let explanation: Explanation = inference.explain_with_answer(&create_grid_fixture(), "caper").unwrap();
+        for (row, guesses) in create_grid_fixture().rows.iter().zip(&explanation.rows) {
+            for guess in &library.guesses {
+                assert_eq!(guesses.contains(&guess.as_str()), Pattern::evaluate(guess, "caper") == *row);
+            }
+        }
+        // slate and stale give caper the same feedback
+        assert_eq!(explanation.rows[0], ["slate", "stale"]);
+        assert_eq!(explanation.rows[3], ["caper"]);
+        assert_eq!(inference.explain_with_answer(&create_grid_fixture(), "plumb"), None);
+    }
+
+    #[test]
+    fn test_unknown_answers_are_inferred() {
+        let library: Library = create_word_library(&ANSWERS, &GUESS_ONLY);
+        let inference: GridInference = GridInference::new(&library);
+        let explanations: Vec<Explanation> = inference.explain(&create_grid_fixture());
+        assert_eq!(explanations.iter().map(|e| e.answer.as_str()).collect::<Vec<&str>>(), ["caper"]);
+        assert_eq!(GridInference::openers(&explanations), ["slate", "stale"]);
+
+        // A one row loss could have been played against any answer the guess did not hit
+        let grid: ShareGrid = ShareGrid::from_results(1, &[GuessResult::evaluate_guess("zzzzz", "eerie")], 1, false);
+        assert_eq!(inference.explain(&grid).len(), library.answers.len());
+    }
+
+}
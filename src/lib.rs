@@ -1,120 +1,435 @@
 //! Wordle solver main module.
 //!
 //! Provides logic for evaluating guesses, representing letter states, and loading word libraries.
-//! Run this binary to test the guess evaluation logic.
+//! The crate's binary drives this library as an interactive solver.
 
 // Standard library imports
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
 use std::fs;
+use std::io;
 use std::path::Path;
+use std::path::PathBuf;
+
+// Local crate modules
+pub mod absurdle;
+pub mod alphabet;
+pub mod feedback;
+pub mod filter;
+pub mod game;
+pub mod hard_mode;
+pub mod infer;
+pub mod matrix;
+pub mod multi;
+pub mod normalize;
+pub mod optimal;
+pub mod pattern;
+pub mod render;
+pub mod rules;
+pub mod share;
+pub mod simulate;
+pub mod solver;
+pub mod tree;
+
+// Local crate imports
+use alphabet::Alphabet;
+use normalize::Normalization;
+use normalize::NormalizationReport;
+use pattern::MAX_PATTERN_LENGTH;
+use rules::FeedbackRules;
+use rules::NytRules;
 
 /// State of a letter in a guess
-#[derive(PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LetterState {
 
     /// The letter is in the correct position
     Correct,
 
-    /// The letter is in the word, but not in this position, and the position it is in is not already marked as Correct
+    /// The letter is in the word, but not in this position. Under the default rules, each copy of the letter
+    /// in the answer that is not already marked Correct can only make one guess letter Present.
     Present,
 
-    /// The letter is not in the word
+    /// The letter is not in the word, or every copy of it in the word is already accounted for
     Absent,
 }
 
 /// Result of a guess
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "GuessStates"))]
 pub struct GuessResult {
     pub guess: String,
     states: Vec<LetterState>,
 }
 
-/// A library of valid words
+/// Serialized form of a guess result, checked when deserialized
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct GuessStates {
+    guess: String,
+    states: Vec<LetterState>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<GuessStates> for GuessResult {
+
+    type Error = feedback::FeedbackError;
+
+    fn try_from(result: GuessStates) -> Result<GuessResult, feedback::FeedbackError> {
+        let expected: usize = result.guess.chars().count();
+        if result.states.len() != expected {
+            return Err(feedback::FeedbackError::WrongLength { expected, found: result.states.len() });
+        }
+        if expected > MAX_PATTERN_LENGTH {
+            return Err(feedback::FeedbackError::TooLong { found: expected, max: MAX_PATTERN_LENGTH });
+        }
+        Ok(GuessResult { guess: result.guess, states: result.states })
+    }
+
+}
+
+/// A library of valid words.
+/// Build libraries with Library::new or by loading them so that the word indices are kept.
+/// Only the words and alphabet are serialized; the indices are rebuilt when a library is deserialized.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "LibraryWords"))]
 pub struct Library {
     pub guesses: Vec<String>,
     pub answers: Vec<String>,
     pub word_length: usize,
+
+    /// Letters the words are drawn from, if the library declares them
+    pub alphabet: Option<Alphabet>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    guess_indices: HashMap<String, usize>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    answer_indices: HashMap<String, usize>,
 }
 
-/// Load words from a file into a vector of strings, ensuring all words have the same length.
-/// Returns (words, word_length).
-fn load_words_from_file(path: &Path) -> (Vec<String>, usize) {
-    let contents: String = fs::read_to_string(path).expect(
-        &format!("Something went wrong reading the file: {}", path.display())
-    );
-    let words: Vec<String> = contents.lines().map(|line| line.to_string()).collect();
-    let word_length = words.first().map(|w| w.len()).unwrap_or(0);
-    if !words.iter().all(|w| w.len() == word_length) {
-        panic!("Not all words have the same length in file: {:?}", path);
+/// Serialized form of a library, checked and indexed again when deserialized
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct LibraryWords {
+    guesses: Vec<String>,
+    answers: Vec<String>,
+    #[serde(default)]
+    alphabet: Option<Alphabet>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<LibraryWords> for Library {
+
+    type Error = LibraryError;
+
+    fn try_from(words: LibraryWords) -> Result<Library, LibraryError> {
+        let library: Library = Library::with_policy(words.guesses, words.answers, AnswerPolicy::Independent)?;
+        match words.alphabet {
+            Some(alphabet) => library.with_alphabet(alphabet),
+            None => Ok(library),
+        }
     }
-    (words, word_length)
+
 }
 
-impl Library {
+/// How a library treats answers that are missing from its guesses
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum AnswerPolicy {
 
-    /// Load a library from a file
-    pub fn load_from_file(guesses_path: &Path, answers_path: &Path) -> Library {
-        let (guesses, guesses_word_length) = load_words_from_file(guesses_path);
-        let (answers, answers_word_length) = load_words_from_file(answers_path);
-        if guesses_word_length != answers_word_length {
-            panic!("Guesses and answers must have the same word length: {} != {}", guesses_word_length, answers_word_length);
+    /// Append missing answers to the guesses
+    #[default]
+    Merge,
+
+    /// Fail to build the library if any answer is missing from the guesses
+    Require,
+
+    /// Keep the guesses and answers independent
+    Independent,
+}
+
+/// Options used when loading a library from files
+#[derive(Clone, Debug, Default)]
+pub struct LoadOptions {
+    pub normalization: Normalization,
+    pub answer_policy: AnswerPolicy,
+}
+
+/// Positions of a word in the guesses and answers of a library
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WordIndex {
+    pub guess: Option<usize>,
+    pub answer: Option<usize>,
+}
+
+/// Error raised when a library cannot be loaded
+#[derive(Debug)]
+pub enum LibraryError {
+
+    /// The file could not be read
+    Io { path: PathBuf, source: io::Error },
+
+    /// The file contains no words
+    Empty { path: PathBuf },
+
+    /// A word does not have the same length as the first word in the file
+    InconsistentWordLength { path: PathBuf, line: usize, word: String, expected: usize, found: usize },
+
+    /// The guesses and answers files contain words of different lengths
+    LengthMismatch { guesses: usize, answers: usize },
+
+    /// A word passed to Library::new does not have the same length as the first guess
+    InvalidWordLength { word: String, expected: usize, found: usize },
+
+    /// An answer is not one of the guesses and the answer policy requires it to be
+    AnswerNotGuessable { word: String },
+
+    /// The words are longer than feedback patterns support
+    UnsupportedWordLength { found: usize, max: usize },
+
+    /// A word contains a letter outside the library's alphabet
+    InvalidLetter { word: String, letter: char, alphabet: String },
+}
+
+impl fmt::Display for LibraryError {
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LibraryError::Io { path, source } => {
+                write!(f, "Something went wrong reading the file {}: {}", path.display(), source)
+            },
+            LibraryError::Empty { path } => {
+                write!(f, "No words found in file: {}", path.display())
+            },
+            LibraryError::InconsistentWordLength { path, line, word, expected, found } => write!(
+                f, "Word {:?} on line {} of {} has length {}, expected {}",
+                word, line, path.display(), found, expected
+            ),
+            LibraryError::LengthMismatch { guesses, answers } => write!(
+                f, "Guesses and answers must have the same word length: {} != {}", guesses, answers
+            ),
+            LibraryError::InvalidWordLength { word, expected, found } => write!(
+                f, "Word {:?} has length {}, expected {}", word, found, expected
+            ),
+            LibraryError::AnswerNotGuessable { word } => {
+                write!(f, "Answer {:?} is not in the list of guesses", word)
+            },
+            LibraryError::UnsupportedWordLength { found, max } => {
+                write!(f, "Words have {} letters, but at most {} are supported", found, max)
+            },
+            LibraryError::InvalidLetter { word, letter, alphabet } => write!(
+                f, "Word {:?} contains {:?}, which is not in the {} alphabet", word, letter, alphabet
+            ),
         }
-        Library { guesses, answers, word_length: guesses_word_length }
     }
 
 }
 
-impl LetterState {
+impl Error for LibraryError {
 
-    /// Stringify letter state into emojis for console output
-    pub fn to_string(&self) -> char {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
-            LetterState::Correct => '🟩',
-            LetterState::Present => '🟨',
-            LetterState::Absent => '🟥',
+            LibraryError::Io { source, .. } => Some(source),
+            _ => None,
         }
     }
 
 }
 
+/// Load words from a file into a vector of strings, ensuring all words have the same length.
+/// Returns (words, word_length, report).
+fn load_words_from_file(
+    path: &Path,
+    normalization: &Normalization,
+) -> Result<(Vec<String>, usize, NormalizationReport), LibraryError> {
+    let contents: String = fs::read_to_string(path).map_err(|source| {
+        LibraryError::Io { path: path.to_path_buf(), source }
+    })?;
+    let (lines, report) = normalization.apply(&contents);
+    let word_length: usize = match lines.first() {
+        Some((_, word)) => word.chars().count(),
+        None => return Err(LibraryError::Empty { path: path.to_path_buf() }),
+    };
+    if let Some((line, word)) = lines.iter().find(|(_, w)| w.chars().count() != word_length) {
+        return Err(LibraryError::InconsistentWordLength {
+            path: path.to_path_buf(),
+            line: *line,
+            word: word.clone(),
+            expected: word_length,
+            found: word.chars().count(),
+        });
+    }
+    let words: Vec<String> = lines.into_iter().map(|(_, word)| word).collect();
+    Ok((words, word_length, report))
+}
+
+/// What normalization did to each word list of a library
+#[derive(Debug)]
+pub struct LibraryReport {
+    pub guesses: NormalizationReport,
+    pub answers: NormalizationReport,
+}
+
+impl Library {
+
+    /// Create a library from word lists, merging any answers that are missing into the guesses
+    pub fn new(guesses: Vec<String>, answers: Vec<String>) -> Result<Library, LibraryError> {
+        Library::with_policy(guesses, answers, AnswerPolicy::default())
+    }
+
+    /// Create a library from word lists, treating answers missing from the guesses according to the policy
+    pub fn with_policy(
+        mut guesses: Vec<String>,
+        answers: Vec<String>,
+        policy: AnswerPolicy,
+    ) -> Result<Library, LibraryError> {
+        let word_length: usize = guesses.first().or(answers.first()).map(|w| w.chars().count()).unwrap_or(0);
+        if word_length > MAX_PATTERN_LENGTH {
+            return Err(LibraryError::UnsupportedWordLength { found: word_length, max: MAX_PATTERN_LENGTH });
+        }
+        if let Some(word) = guesses.iter().chain(&answers).find(|w| w.chars().count() != word_length) {
+            return Err(LibraryError::InvalidWordLength {
+                word: word.clone(),
+                expected: word_length,
+                found: word.chars().count(),
+            });
+        }
+        let mut guess_indices: HashMap<String, usize> = index_words(&guesses);
+        if policy != AnswerPolicy::Independent {
+            for answer in &answers {
+                if guess_indices.contains_key(answer) {
+                    continue;
+                }
+                if policy == AnswerPolicy::Require {
+                    return Err(LibraryError::AnswerNotGuessable { word: answer.clone() });
+                }
+                guess_indices.insert(answer.clone(), guesses.len());
+                guesses.push(answer.clone());
+            }
+        }
+        let answer_indices: HashMap<String, usize> = index_words(&answers);
+        Ok(Library { guesses, answers, word_length, alphabet: None, guess_indices, answer_indices })
+    }
+
+    /// Declare the alphabet of the library, checking that every word only uses its letters
+    pub fn with_alphabet(self, alphabet: Alphabet) -> Result<Library, LibraryError> {
+        for word in self.guesses.iter().chain(&self.answers) {
+            if let Some(letter) = alphabet.invalid_letter(word) {
+                let alphabet: String = alphabet.name().to_string();
+                return Err(LibraryError::InvalidLetter { word: word.clone(), letter, alphabet });
+            }
+        }
+        Ok(Library { alphabet: Some(alphabet), ..self })
+    }
+
+    /// Load a library from a file, cleaning up the word lists with the default options
+    pub fn load_from_file(guesses_path: &Path, answers_path: &Path) -> Result<Library, LibraryError> {
+        Library::load_from_file_with(guesses_path, answers_path, &LoadOptions::default())
+            .map(|(library, _)| library)
+    }
+
+    /// Load a library from a file with the given options, reporting what normalization changed or rejected
+    pub fn load_from_file_with(
+        guesses_path: &Path,
+        answers_path: &Path,
+        options: &LoadOptions,
+    ) -> Result<(Library, LibraryReport), LibraryError> {
+        let (guesses, guesses_word_length, guesses_report) = load_words_from_file(guesses_path, &options.normalization)?;
+        let (answers, answers_word_length, answers_report) = load_words_from_file(answers_path, &options.normalization)?;
+        if guesses_word_length != answers_word_length {
+            return Err(LibraryError::LengthMismatch { guesses: guesses_word_length, answers: answers_word_length });
+        }
+        let mut library: Library = Library::with_policy(guesses, answers, options.answer_policy)?;
+        library.alphabet = options.normalization.alphabet.clone();
+        Ok((library, LibraryReport { guesses: guesses_report, answers: answers_report }))
+    }
+
+    /// Position of a word in the guesses
+    pub fn guess_index(&self, word: &str) -> Option<usize> {
+        find_indexed(&self.guesses, &self.guess_indices, word)
+    }
+
+    /// Position of a word in the answers
+    pub fn answer_index(&self, word: &str) -> Option<usize> {
+        find_indexed(&self.answers, &self.answer_indices, word)
+    }
+
+    /// Positions of a word in both the guesses and the answers
+    pub fn lookup(&self, word: &str) -> WordIndex {
+        WordIndex { guess: self.guess_index(word), answer: self.answer_index(word) }
+    }
+
+    /// Whether a word is an allowed guess
+    pub fn is_guess(&self, word: &str) -> bool {
+        self.guess_index(word).is_some()
+    }
+
+    /// Whether a word is a possible answer
+    pub fn is_answer(&self, word: &str) -> bool {
+        self.answer_index(word).is_some()
+    }
+
+}
+
+/// Map each word to its first position in a list
+pub(crate) fn index_words(words: &[String]) -> HashMap<String, usize> {
+    let mut indices: HashMap<String, usize> = HashMap::with_capacity(words.len());
+    for (index, word) in words.iter().enumerate() {
+        indices.entry(word.clone()).or_insert(index);
+    }
+    indices
+}
+
+/// Find a word through an index, falling back to a linear search if the list was modified after indexing
+fn find_indexed(words: &[String], indices: &HashMap<String, usize>, word: &str) -> Option<usize> {
+    match indices.get(word) {
+        Some(&index) if words.get(index).is_some_and(|w| w == word) => Some(index),
+        _ => words.iter().position(|w| w == word),
+    }
+}
+
 impl GuessResult {
 
-    /// Compares two strings of equal length and returns a Vec of LetterState
+    /// Compares two words with the same number of letters using the New York Times rules
     pub fn evaluate_guess(guess: &str, answer: &str) -> GuessResult {
-        if guess.len() != answer.len() {
-            panic!("Guess and answer must be the same length");
-        }
+        GuessResult::evaluate_guess_with(&NytRules, guess, answer)
+    }
+
+    /// Compares two words with the same number of letters using the given rule set.
+    /// Letters are chars, so words should use composed characters (see Normalization::compose).
+    pub fn evaluate_guess_with(rules: &dyn FeedbackRules, guess: &str, answer: &str) -> GuessResult {
         let guess_chars: Vec<char> = guess.chars().collect();
         let answer_chars: Vec<char> = answer.chars().collect();
-        let states: Vec<LetterState> = (0..guess_chars.len()).map(|i: usize| {
-            evaluate_letter(&guess_chars, &answer_chars, i)
-        }).collect();
+        if guess_chars.len() != answer_chars.len() {
+            panic!("Guess and answer must be the same length");
+        }
+        let mut states: Vec<LetterState> = guess_chars.iter().map(|_| LetterState::Absent).collect();
+        rules.evaluate(&guess_chars, &answer_chars, &mut states);
         GuessResult { guess: guess.to_string(), states }
     }
 
-    /// Stringify guess result into emojis for console output
-    pub fn to_string(&self) -> String {
-        self.states.iter().map(|s| s.to_string()).collect()
+    /// Creates a result from feedback that was observed for a guess, e.g. in a game played elsewhere
+    pub fn from_states(guess: &str, states: Vec<LetterState>) -> GuessResult {
+        if guess.chars().count() != states.len() {
+            panic!("Guess and feedback must be the same length");
+        }
+        GuessResult { guess: guess.to_string(), states }
     }
 
-}
-
-/// Evaluates a single letter in a guess against the answer
-fn evaluate_letter(guess: &[char], answer: &[char], i: usize) -> LetterState {
-    let g: char = guess[i];
-    let a: char = answer[i];
-    if g == a {
-        LetterState::Correct
-    } else if answer.iter().enumerate().any(|(j, &ac)| j != i && ac == g && guess[j] != ac) {
-        LetterState::Present
-    } else {
-        LetterState::Absent
+    /// State of each letter in the guess
+    pub fn states(&self) -> &[LetterState] {
+        &self.states
     }
+
 }
 
 #[cfg(test)]
 mod tests {
 
     // Standard library imports
-    use std::path::PathBuf;
+    use std::collections::HashSet;
     use std::sync::OnceLock;
 
     // External crate imports
@@ -134,7 +449,7 @@ mod tests {
             let guesses_path: PathBuf = data_root.join("allowed.txt");
             let answers_path: PathBuf = data_root.join("allowed.txt");
             // Load the library from the files
-            Library::load_from_file(&guesses_path, &answers_path)
+            Library::load_from_file(&guesses_path, &answers_path).expect("Failed to load library fixture")
         })
     }
 
@@ -157,6 +472,306 @@ mod tests {
         })
     }
 
+    /// Answers of the small library shared by the module tests, none of which repeat a letter
+    pub(crate) const SMALL_LIBRARY_WORDS: [&str; 7] =
+        ["crane", "slate", "trace", "react", "cater", "plumb", "brick"];
+
+    /// Answers of the small library plus "eerie", which repeats a letter
+    pub(crate) const REPEATED_LETTER_WORDS: [&str; 8] =
+        ["crane", "slate", "trace", "react", "cater", "plumb", "brick", "eerie"];
+
+    /// Create a library with the given answers, plus words that are only guesses
+    pub(crate) fn create_word_library(answers: &[&str], guess_only: &[&str]) -> Library {
+        let answers: Vec<String> = answers.iter().map(|w| w.to_string()).collect();
+        let mut guesses: Vec<String> = answers.clone();
+        guesses.extend(guess_only.iter().map(|w| w.to_string()));
+        Library::new(guesses, answers).unwrap()
+    }
+
+    /// Create the small library shared by the module tests, where every word is an answer
+    pub(crate) fn create_small_library() -> Library {
+        create_word_library(&SMALL_LIBRARY_WORDS, &[])
+    }
+
+    /// Generate a library of distinct words of the given length from a small alphabet, so that words
+    /// share letters and repeat them. The same arguments always give the same library.
+    pub(crate) fn create_generated_library(word_length: usize, count: usize) -> Library {
+        let alphabet: Vec<char> = "aeiorstlnc".chars().collect();
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d ^ word_length as u64;
+        let mut words: Vec<String> = Vec::with_capacity(count);
+        while words.len() < count {
+            let word: String = (0..word_length).map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                alphabet[(state % alphabet.len() as u64) as usize]
+            }).collect();
+            if !words.contains(&word) {
+                words.push(word);
+            }
+        }
+        Library::new(words.clone(), words).unwrap()
+    }
+
+    /// Write a word list to a temporary file for testing
+    fn create_word_file(name: &str, contents: &str) -> PathBuf {
+        let path: PathBuf = std::env::temp_dir().join(format!("wordle-{}-{}.txt", name, std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_load_from_file_errors() {
+        let valid: PathBuf = create_word_file("valid", "crane\nslate\n");
+        let empty: PathBuf = create_word_file("empty", "");
+        let ragged: PathBuf = create_word_file("ragged", "crane\nslate\nslates\n");
+        let longer: PathBuf = create_word_file("longer", "cranes\n");
+        let missing: PathBuf = std::env::temp_dir().join("wordle-missing-file.txt");
+
+        assert!(Library::load_from_file(&valid, &valid).is_ok());
+        assert!(matches!(Library::load_from_file(&missing, &valid), Err(LibraryError::Io { .. })));
+        assert!(matches!(Library::load_from_file(&valid, &empty), Err(LibraryError::Empty { .. })));
+        match Library::load_from_file(&ragged, &valid) {
+            Err(LibraryError::InconsistentWordLength { line, word, expected, found, .. }) => {
+                assert_eq!((line, word.as_str(), expected, found), (3, "slates", 5, 6));
+            },
+            _ => panic!("Expected an inconsistent word length error"),
+        }
+        assert!(matches!(
+            Library::load_from_file(&valid, &longer),
+            Err(LibraryError::LengthMismatch { guesses: 5, answers: 6 })
+        ));
+
+        for path in [valid, empty, ragged, longer] {
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_word_lengths() {
+        for word_length in [3, 4, 6, 7, 11] {
+            let library: Library = create_generated_library(word_length, 20);
+            assert_eq!(library.word_length, word_length);
+            assert_eq!(library.answers.len(), 20);
+            let result: GuessResult = GuessResult::evaluate_guess(&library.guesses[0], &library.answers[1]);
+            assert_eq!(result.states().len(), word_length);
+        }
+        let too_long: Vec<String> = vec!["a".repeat(MAX_PATTERN_LENGTH + 1)];
+        assert!(matches!(
+            Library::new(too_long.clone(), too_long),
+            Err(LibraryError::UnsupportedWordLength { found: 21, max: 20 })
+        ));
+    }
+
+    #[test]
+    fn test_load_from_file_normalizes_words() {
+        let guesses: PathBuf = create_word_file("messy-guesses", "# guesses\r\nCRANE\r\nslate \r\n\r\ncrane\r\n");
+        let answers: PathBuf = create_word_file("messy-answers", "slate\nseñal\n");
+        let (library, report) = Library::load_from_file_with(&guesses, &answers, &LoadOptions::default())
+            .expect("Messy word lists should load");
+        assert_eq!(library.guesses, vec!["crane", "slate", "señal"]);
+        assert_eq!(library.answers, vec!["slate", "señal"]);
+        assert_eq!(library.word_length, 5);
+        assert_eq!((report.guesses.changed.len(), report.guesses.rejected.len(), report.guesses.skipped), (2, 1, 2));
+        assert!(report.answers.is_clean());
+        let strict: LoadOptions = LoadOptions {
+            normalization: Normalization::default().with_alphabet(Alphabet::english()),
+            ..LoadOptions::default()
+        };
+        let (library, report) = Library::load_from_file_with(&guesses, &answers, &strict).unwrap();
+        assert_eq!(library.answers, vec!["slate"]);
+        assert_eq!(library.alphabet, Some(Alphabet::english()));
+        assert_eq!(report.answers.rejected.len(), 1);
+        for path in [guesses, answers] {
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_answer_policies() {
+        let guesses: Vec<String> = vec!["crane".to_string(), "slate".to_string()];
+        let answers: Vec<String> = vec!["slate".to_string(), "trace".to_string()];
+
+        let merged: Library = Library::new(guesses.clone(), answers.clone()).unwrap();
+        assert_eq!(merged.guesses, vec!["crane", "slate", "trace"]);
+        assert_eq!(merged.lookup("trace"), WordIndex { guess: Some(2), answer: Some(1) });
+        assert_eq!(merged.lookup("crane"), WordIndex { guess: Some(0), answer: None });
+        assert!(!merged.is_guess("zzzzz"));
+
+        assert!(matches!(
+            Library::with_policy(guesses.clone(), answers.clone(), AnswerPolicy::Require),
+            Err(LibraryError::AnswerNotGuessable { word }) if word == "trace"
+        ));
+
+        let independent: Library = Library::with_policy(guesses, answers, AnswerPolicy::Independent).unwrap();
+        assert_eq!(independent.guesses.len(), 2);
+        assert!(!independent.is_guess("trace"));
+        assert!(independent.is_answer("trace"));
+    }
+
+    #[test]
+    fn test_lookup_survives_modified_word_lists() {
+        let mut library: Library = Library::new(vec!["crane".to_string(), "slate".to_string()], vec![]).unwrap();
+        library.guesses.remove(0);
+        assert_eq!(library.guess_index("slate"), Some(0));
+        assert_eq!(library.guess_index("crane"), None);
+        assert!(matches!(
+            Library::new(vec!["crane".to_string(), "cranes".to_string()], vec![]),
+            Err(LibraryError::InvalidWordLength { found: 6, .. })
+        ));
+    }
+
+    #[test]
+    fn test_results_can_be_debugged_and_cloned() {
+        let result: GuessResult = GuessResult::evaluate_guess("crane", "caper");
+        assert_eq!(result.clone(), result);
+        assert_eq!(format!("{:?}", result.states()[0]), "Correct");
+        assert!(format!("{:?}", result).contains("\"crane\""));
+        let states: HashSet<LetterState> = result.states().iter().copied().collect();
+        assert_eq!(states.len(), 3);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let words: Vec<String> = ["señal", "niños", "crane"].iter().map(|w| w.to_string()).collect();
+        let library: Library = Library::with_policy(words.clone(), words[..2].to_vec(), AnswerPolicy::Independent)
+            .unwrap()
+            .with_alphabet(Alphabet::spanish())
+            .unwrap();
+        let json: String = serde_json::to_string(&library).unwrap();
+        assert!(!json.contains("indices"));
+        let loaded: Library = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, library);
+        // Lookups use the rebuilt indices
+        assert_eq!(loaded.lookup("niños"), WordIndex { guess: Some(1), answer: Some(1) });
+        assert_eq!(loaded.lookup("crane"), WordIndex { guess: Some(2), answer: None });
+
+        let short: Result<GuessResult, _> = serde_json::from_str(r#"{"guess": "crane", "states": ["Correct"]}"#);
+        assert!(short.unwrap_err().to_string().contains("Feedback has 1 letters, expected 5"));
+        let long: String = serde_json::to_string(&GuessResult::from_states("a", vec![LetterState::Absent]))
+            .unwrap()
+            .replace("\"a\"", &format!("\"{}\"", "a".repeat(21)))
+            .replace("[\"Absent\"]", &serde_json::to_string(&vec![LetterState::Absent; 21]).unwrap());
+        assert!(serde_json::from_str::<GuessResult>(&long).unwrap_err().to_string().contains("at most 20"));
+
+        let history: Vec<GuessResult> = vec![
+            GuessResult::evaluate_guess("crane", "caper"),
+            GuessResult::evaluate_guess("caper", "caper"),
+        ];
+        let json: String = serde_json::to_string(&history).unwrap();
+        assert_eq!(serde_json::from_str::<Vec<GuessResult>>(&json).unwrap(), history);
+
+        // Deserialized libraries are checked like any other
+        let invalid: &str = r#"{"guesses": ["crane", "cranes"], "answers": []}"#;
+        let error: String = serde_json::from_str::<Library>(invalid).unwrap_err().to_string();
+        assert!(error.contains("\"cranes\" has length 6"), "Unexpected error: {}", error);
+    }
+
+    /// Render a result as letter codes: G for correct, Y for present and B for absent
+    fn feedback_code(result: &GuessResult) -> String {
+        result.states.iter().map(|state| match state {
+            LetterState::Correct => 'G',
+            LetterState::Present => 'Y',
+            LetterState::Absent => 'B',
+        }).collect()
+    }
+
+    /// Reference implementation of the official rules, counting unmatched answer letters in a map
+    fn reference_feedback(guess: &str, answer: &str) -> String {
+        let guess_chars: Vec<char> = guess.chars().collect();
+        let answer_chars: Vec<char> = answer.chars().collect();
+        let mut remaining: HashMap<char, usize> = HashMap::new();
+        for (g, a) in guess_chars.iter().zip(&answer_chars) {
+            if g != a {
+                *remaining.entry(*a).or_insert(0) += 1;
+            }
+        }
+        guess_chars.iter().zip(&answer_chars).map(|(g, a)| {
+            if g == a {
+                return 'G';
+            }
+            match remaining.get_mut(g) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    'Y'
+                },
+                _ => 'B',
+            }
+        }).collect()
+    }
+
+    #[test]
+    fn test_localized_libraries() {
+        // The first word spells ñ as n followed by a combining tilde
+        let words: PathBuf = create_word_file("spanish", "NIN\u{303}OS\nseñal\ncrane\n");
+        let options: LoadOptions = LoadOptions {
+            normalization: Normalization::default().with_alphabet(Alphabet::spanish()),
+            ..LoadOptions::default()
+        };
+        let (library, _) = Library::load_from_file_with(&words, &words, &options).unwrap();
+        assert_eq!(library.answers, vec!["niños", "señal", "crane"]);
+        assert_eq!(library.word_length, 5);
+        assert_eq!(library.alphabet, Some(Alphabet::spanish()));
+        fs::remove_file(words).unwrap();
+        let cases: [(&str, &str, &str); 3] = [
+            ("niños", "señal", "BBGBY"),
+            ("größe", "grüße", "GGBGG"),
+            ("слово", "совок", "GBYYY"),
+        ];
+        for (guess, answer, expected) in cases {
+            let result: GuessResult = GuessResult::evaluate_guess(guess, answer);
+            assert_eq!(feedback_code(&result), expected, "Wrong feedback for {} against {}", guess, answer);
+        }
+        assert!(!filter::matches("sen\u{303}al", &GuessResult::evaluate_guess("niños", "señal")));
+        let russian: Vec<String> = vec!["слово".to_string(), "совок".to_string()];
+        assert!(Library::new(russian.clone(), russian).unwrap().with_alphabet(Alphabet::russian()).is_ok());
+        let mixed: Vec<String> = vec!["слово".to_string(), "crane".to_string()];
+        assert!(matches!(
+            Library::new(mixed.clone(), mixed).unwrap().with_alphabet(Alphabet::russian()),
+            Err(LibraryError::InvalidLetter { letter: 'c', .. })
+        ));
+    }
+
+    #[test]
+    fn test_duplicate_letter_regressions() {
+        let cases: [(&str, &str, &str); 12] = [
+            ("eerie", "ether", "GYYBB"),
+            ("ether", "eerie", "GBBYY"),
+            ("eerie", "crane", "BBYBG"),
+            ("speed", "abide", "BBYBY"),
+            ("speed", "erase", "YBYYB"),
+            ("llama", "hello", "YYBBB"),
+            ("lolly", "hello", "BYGGB"),
+            ("hello", "lolly", "BBGGY"),
+            ("abbey", "kebab", "YYGYB"),
+            ("error", "robot", "BYBGB"),
+            ("geese", "eerie", "BGYBG"),
+            ("mamma", "maxim", "GGYBB"),
+        ];
+        for (guess, answer, expected) in cases {
+            let result: GuessResult = GuessResult::evaluate_guess(guess, answer);
+            assert_eq!(feedback_code(&result), expected, "Wrong feedback for {} against {}", guess, answer);
+        }
+    }
+
+    #[test]
+    fn test_duplicate_letters_match_reference_rules() {
+        let words: [&str; 16] = [
+            "eerie", "ether", "speed", "abide", "erase", "llama", "hello", "lolly",
+            "abbey", "kebab", "error", "robot", "geese", "mamma", "sassy", "asses",
+        ];
+        for guess in words {
+            for answer in words {
+                let result: GuessResult = GuessResult::evaluate_guess(guess, answer);
+                assert_eq!(
+                    feedback_code(&result), reference_feedback(guess, answer),
+                    "Wrong feedback for {} against {}", guess, answer
+                );
+            }
+        }
+    }
+
     #[test]
     #[ignore = "This test is slow and should not run by default"]
     fn test_evaluate_all_guess_answer_pairs() {
@@ -179,6 +794,15 @@ mod tests {
                 // Check result
                 let result: GuessResult = GuessResult::evaluate_guess(guess, answer);
                 
+                // Copies of a letter in the answer that are not at a correct position
+                let unmatched_copies = |letter: char| answer_chars.iter().enumerate().filter(|&(j, &ac)| {
+                    ac == letter && result.states[j] != LetterState::Correct
+                }).count();
+                // Copies of the letter at an index already claimed by earlier Present letters in the guess
+                let claimed_copies = |index: usize| (0..index).filter(|&j| {
+                    guess_chars[j] == guess_chars[index] && result.states[j] == LetterState::Present
+                }).count();
+
                 // Double check results
                 for (index, state) in result.states.iter().enumerate() {
                     let guess_char = guess_chars[index];
@@ -198,11 +822,10 @@ mod tests {
                                 "Expected letter {} to be present in answer {} but not in a correct position",
                                 guess_char, answer
                             );
-                            // Ensure the guess letter is not present in the answer (unless it is at a correct position)
+                            // Ensure every copy of the letter in the answer (outside correct positions) has
+                            // already been claimed by an earlier Present
                             assert!(
-                                !answer_chars.iter().enumerate().any(|(j, &ac)| {
-                                    ac == guess_char && result.states[j] != LetterState::Correct
-                                }),
+                                claimed_copies(index) >= unmatched_copies(guess_char),
                                 "Expected letter {} to be absent in answer {}", guess_char, answer
                             );
                         },
@@ -212,11 +835,10 @@ mod tests {
                                 guess_char, answer_char, 
                                 "Expected letter {} to be absent in answer {}", guess_char, answer
                             );
-                            // Ensure the guess letter is present in the answer somewhere that is not a correct position
+                            // Ensure the answer has a copy of the letter outside correct positions that has not
+                            // been claimed by an earlier Present
                             assert!(
-                                answer_chars.iter().enumerate().any(|(j, &ac)| {
-                                    ac == guess_char && result.states[j] != LetterState::Correct
-                                }),
+                                claimed_copies(index) < unmatched_copies(guess_char),
                                 "Expected letter {} to be absent in answer {}", guess_char, answer
                             );
                         },
@@ -0,0 +1,733 @@
+//! Interactive Wordle solver.
+//!
+//! Suggests guesses for a game being played elsewhere. After each guess, type the feedback the game
+//! showed and the solver narrows down the possible answers and suggests the next guess.
+//!
+//! Pass --simulate to play every answer in the library instead and print how many guesses were needed.
+//! Pass --export to save the strategy's complete decision tree, and --tree to play from a saved tree
+//! by lookup instead of running the strategy.
+//!
+//! Pass --play to play a game against a random answer from the library, or --answer to choose it.
+//! The random answer is picked from a seed, which --seed sets so that a game can be replayed.
+//!
+//! Pass --absurdle to play against a host that avoids committing to an answer, as Absurdle does. With
+//! --simulate, the strategy plays against that host instead.
+//!
+//! Pass --boards to get help with a game that scores every guess against several answers at once,
+//! such as Dordle (2), Quordle (4) or Octordle (8).
+//!
+//! Pass --theme to choose how feedback is shown: emoji squares in the dark, light or high contrast
+//! (contrast) theme, colored letters for terminals (ansi), feedback codes (ascii) or HTML tiles (html).
+//!
+//! Usage: rust-wordle-solver <guesses file> <answers file> [--cache <directory>] [--rules nyt|naive]
+//!        [--strategy entropy|minimax] [--hard] [--simulate] [--max-turns <n>]
+//!        [--export <file> | --tree <file>] [--alphabet en|es|de|ru] [--boards <n>] [--absurdle]
+//!        [--play] [--answer <word>] [--seed <n>] [--theme dark|light|contrast|ansi|ascii|html]
+
+// Standard library imports
+use std::env;
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+// External crate imports
+use unicode_normalization::UnicodeNormalization;
+
+// Local crate imports
+use rust_wordle_solver::absurdle::AdversarialHost;
+use rust_wordle_solver::alphabet::Alphabet;
+use rust_wordle_solver::feedback::FeedbackError;
+use rust_wordle_solver::game::Game;
+use rust_wordle_solver::game::GameStatus;
+use rust_wordle_solver::matrix::PatternMatrix;
+use rust_wordle_solver::multi::MultiSolver;
+use rust_wordle_solver::normalize::Normalization;
+use rust_wordle_solver::render::AnsiRenderer;
+use rust_wordle_solver::render::AsciiRenderer;
+use rust_wordle_solver::render::EmojiRenderer;
+use rust_wordle_solver::render::HtmlRenderer;
+use rust_wordle_solver::render::Renderer;
+use rust_wordle_solver::rules::FeedbackRules;
+use rust_wordle_solver::rules::NaiveRules;
+use rust_wordle_solver::rules::NytRules;
+use rust_wordle_solver::simulate::Simulation;
+use rust_wordle_solver::simulate::SimulationReport;
+use rust_wordle_solver::solver::EntropyStrategy;
+use rust_wordle_solver::solver::MinimaxStrategy;
+use rust_wordle_solver::solver::Solver;
+use rust_wordle_solver::solver::Strategy;
+use rust_wordle_solver::tree::DecisionTree;
+use rust_wordle_solver::GuessResult;
+use rust_wordle_solver::LetterState;
+use rust_wordle_solver::Library;
+use rust_wordle_solver::LoadOptions;
+
+/// Strategies that can be chosen on the command line
+#[derive(Clone, Copy)]
+enum StrategyKind {
+    Entropy,
+    Minimax,
+}
+
+/// Command line options
+struct Options {
+    guesses_path: String,
+    answers_path: String,
+    cache_dir: Option<String>,
+    rules: Arc<dyn FeedbackRules>,
+    strategy: StrategyKind,
+    hard_mode: bool,
+    simulate: bool,
+    max_turns: usize,
+    export_path: Option<String>,
+    tree_path: Option<String>,
+    alphabet: Option<Alphabet>,
+    boards: usize,
+    absurdle: bool,
+    play: bool,
+    answer: Option<String>,
+    seed: Option<u64>,
+    renderer: Arc<dyn Renderer>,
+}
+
+fn main() -> ExitCode {
+    let options: Options = match parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            eprintln!(concat!(
+                "Usage: rust-wordle-solver <guesses file> <answers file> [--cache <directory>] [--rules nyt|naive] ",
+                "[--strategy entropy|minimax] [--hard] [--simulate] [--max-turns <n>] ",
+                "[--export <file> | --tree <file>] [--alphabet en|es|de|ru] [--boards <n>] [--absurdle] ",
+                "[--play] [--answer <word>] [--seed <n>] [--theme dark|light|contrast|ansi|ascii|html]",
+            ));
+            return ExitCode::FAILURE;
+        }
+    };
+    let library: Library = match Library::load_from_file_with(
+        Path::new(&options.guesses_path),
+        Path::new(&options.answers_path),
+        &LoadOptions {
+            normalization: match &options.alphabet {
+                Some(alphabet) => Normalization::default().with_alphabet(alphabet.clone()),
+                None => Normalization::default(),
+            },
+            ..LoadOptions::default()
+        },
+    ) {
+        Ok((library, report)) => {
+            for (name, list) in [("guesses", &report.guesses), ("answers", &report.answers)] {
+                for rejected in &list.rejected {
+                    eprintln!("Skipped {} line {} ({:?}): {:?}", name, rejected.line, rejected.word, rejected.reason);
+                }
+            }
+            library
+        },
+        Err(error) => {
+            eprintln!("{}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+    let matrix: Option<Arc<PatternMatrix>> = options.cache_dir.as_ref().and_then(|dir| {
+        match PatternMatrix::load_or_compute_with(&library, options.rules.as_ref(), Path::new(dir)) {
+            Ok(matrix) => Some(Arc::new(matrix)),
+            Err(error) => {
+                eprintln!("Could not use pattern cache in {}: {}", dir, error);
+                None
+            }
+        }
+    });
+    if options.boards > 1 {
+        let mut solver: MultiSolver = MultiSolver::new(&library, options.boards).with_rules(options.rules.clone());
+        if let Some(matrix) = matrix {
+            solver = solver.with_matrix(matrix);
+        }
+        return match run_boards(solver, io::stdin().lock(), io::stdout().lock()) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("{}", error);
+                ExitCode::FAILURE
+            }
+        };
+    }
+    if options.play {
+        let answer: String = match &options.answer {
+            Some(answer) => answer.nfc().collect::<String>().to_lowercase(),
+            None => choose_answer(&library, options.seed.unwrap_or_else(clock_seed)).to_string(),
+        };
+        let game: Game = match Game::new(&library, &answer) {
+            Ok(game) => game
+                .with_max_turns(options.max_turns)
+                .with_rules(options.rules.clone())
+                .with_hard_mode(options.hard_mode),
+            Err(error) => {
+                eprintln!("{}", error);
+                return ExitCode::FAILURE;
+            }
+        };
+        return match run_game(game, options.renderer.as_ref(), io::stdin().lock(), io::stdout().lock()) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("{}", error);
+                ExitCode::FAILURE
+            }
+        };
+    }
+    if options.absurdle && !options.simulate {
+        let host: AdversarialHost = AdversarialHost::new(&library).with_rules(options.rules.clone());
+        let renderer: &dyn Renderer = options.renderer.as_ref();
+        return match run_absurdle(&library, host, renderer, io::stdin().lock(), io::stdout().lock()) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("{}", error);
+                ExitCode::FAILURE
+            }
+        };
+    }
+    let strategy: Box<dyn Strategy + Sync> = build_strategy(options.strategy, matrix, options.rules.clone());
+    let tree: Option<DecisionTree> = match options.tree_path.as_ref().map(|path| DecisionTree::load(Path::new(path))) {
+        Some(Ok(tree)) => Some(tree),
+        Some(Err(error)) => {
+            eprintln!("{}", error);
+            return ExitCode::FAILURE;
+        },
+        None => None,
+    };
+    let simulation: Simulation = Simulation::new(options.max_turns)
+        .with_rules(options.rules.clone())
+        .with_hard_mode(options.hard_mode);
+    if let Some(path) = &options.export_path {
+        let Some(tree) = simulation.decision_tree(&library, &strategy) else {
+            eprintln!("The strategy does not solve every answer within {} guesses", options.max_turns);
+            return ExitCode::FAILURE;
+        };
+        if let Err(error) = tree.save(Path::new(path)) {
+            eprintln!("Could not write {}: {}", path, error);
+            return ExitCode::FAILURE;
+        }
+        println!(
+            "Wrote decision tree to {}: mean {:.4} guesses, at most {}",
+            path, tree.average_guesses(), tree.depth()
+        );
+        return ExitCode::SUCCESS;
+    }
+    if options.simulate && options.absurdle {
+        match simulation.play_adversary(&library, &strategy) {
+            Some(turns) => println!("Beat the adversary in {} guesses", turns),
+            None => println!("Did not beat the adversary within {} guesses", options.max_turns),
+        }
+        return ExitCode::SUCCESS;
+    }
+    if options.simulate {
+        match &tree {
+            Some(tree) => print_report(&simulation.run_tree(&library, tree)),
+            None => print_report(&simulation.run(&library, &strategy)),
+        }
+        return ExitCode::SUCCESS;
+    }
+    let solver: Solver<Box<dyn Strategy + Sync>> = Solver::new(&library, strategy)
+        .with_rules(options.rules)
+        .with_hard_mode(options.hard_mode);
+    match run(solver, tree.as_ref(), io::stdin().lock(), io::stdout().lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Parse command line arguments
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut positional: Vec<String> = Vec::new();
+    let mut cache_dir: Option<String> = None;
+    let mut rules: Arc<dyn FeedbackRules> = Arc::new(NytRules);
+    let mut strategy: StrategyKind = StrategyKind::Entropy;
+    let mut hard_mode: bool = false;
+    let mut simulate: bool = false;
+    let mut max_turns: usize = 6;
+    let mut export_path: Option<String> = None;
+    let mut tree_path: Option<String> = None;
+    let mut alphabet: Option<Alphabet> = None;
+    let mut boards: usize = 1;
+    let mut absurdle: bool = false;
+    let mut play: bool = false;
+    let mut answer: Option<String> = None;
+    let mut seed: Option<u64> = None;
+    let mut renderer: Arc<dyn Renderer> = Arc::new(EmojiRenderer::dark());
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--cache" => cache_dir = Some(args.next().ok_or("--cache needs a directory")?),
+            "--rules" => rules = match args.next().as_deref() {
+                Some("nyt") => Arc::new(NytRules),
+                Some("naive") => Arc::new(NaiveRules),
+                _ => return Err("--rules needs one of: nyt, naive".to_string()),
+            },
+            "--strategy" => strategy = match args.next().as_deref() {
+                Some("entropy") => StrategyKind::Entropy,
+                Some("minimax") => StrategyKind::Minimax,
+                _ => return Err("--strategy needs one of: entropy, minimax".to_string()),
+            },
+            "--hard" => hard_mode = true,
+            "--simulate" => simulate = true,
+            "--max-turns" => max_turns = match args.next().map(|n| n.parse::<usize>()) {
+                Some(Ok(n)) if n > 0 => n,
+                _ => return Err("--max-turns needs a positive number".to_string()),
+            },
+            "--export" => export_path = Some(args.next().ok_or("--export needs a file")?),
+            "--tree" => tree_path = Some(args.next().ok_or("--tree needs a file")?),
+            "--alphabet" => alphabet = match args.next().as_deref().and_then(Alphabet::by_name) {
+                Some(alphabet) => Some(alphabet),
+                None => return Err("--alphabet needs one of: en, es, de, ru".to_string()),
+            },
+            "--absurdle" => absurdle = true,
+            "--play" => play = true,
+            "--answer" => {
+                answer = Some(args.next().ok_or("--answer needs a word")?);
+                play = true;
+            },
+            "--seed" => {
+                seed = match args.next().map(|n| n.parse::<u64>()) {
+                    Some(Ok(n)) => Some(n),
+                    _ => return Err("--seed needs a number".to_string()),
+                };
+                play = true;
+            },
+            "--theme" => renderer = match args.next().as_deref() {
+                Some("dark") => Arc::new(EmojiRenderer::dark()),
+                Some("light") => Arc::new(EmojiRenderer::light()),
+                Some("contrast") => Arc::new(EmojiRenderer::high_contrast()),
+                Some("ansi") => Arc::new(AnsiRenderer),
+                Some("ascii") => Arc::new(AsciiRenderer),
+                Some("html") => Arc::new(HtmlRenderer),
+                _ => return Err("--theme needs one of: dark, light, contrast, ansi, ascii, html".to_string()),
+            },
+            "--boards" => boards = match args.next().map(|n| n.parse::<usize>()) {
+                Some(Ok(n)) if n > 0 => n,
+                _ => return Err("--boards needs a positive number".to_string()),
+            },
+            _ if arg.starts_with("--") => return Err(format!("Unknown option: {}", arg)),
+            _ => positional.push(arg),
+        }
+    }
+    if export_path.is_some() && tree_path.is_some() {
+        return Err("--export and --tree cannot be used together".to_string());
+    }
+    if boards > 1 && (simulate || hard_mode || export_path.is_some() || tree_path.is_some() || absurdle) {
+        return Err("--boards cannot be used with --simulate, --hard, --export, --tree or --absurdle".to_string());
+    }
+    if absurdle && (export_path.is_some() || tree_path.is_some()) {
+        return Err("--absurdle cannot be used with --export or --tree".to_string());
+    }
+    if play && (simulate || absurdle || boards > 1 || export_path.is_some() || tree_path.is_some()) {
+        return Err("--play cannot be used with --simulate, --absurdle, --boards, --export or --tree".to_string());
+    }
+    match <[String; 2]>::try_from(positional) {
+        Ok([guesses_path, answers_path]) => Ok(Options {
+            guesses_path, answers_path, cache_dir, rules, strategy, hard_mode, simulate, max_turns,
+            export_path, tree_path, alphabet, boards, absurdle, play, answer, seed, renderer,
+        }),
+        Err(_) => Err("Expected a guesses file and an answers file".to_string()),
+    }
+}
+
+/// Pick the answer for --play from a seed. The seed is scrambled with the SplitMix64 finalizer first,
+/// so that close seeds, such as clock readings taken moments apart, pick unrelated answers.
+fn choose_answer(library: &Library, seed: u64) -> &str {
+    let mut mixed: u64 = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    mixed = (mixed ^ (mixed >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    mixed = (mixed ^ (mixed >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    mixed ^= mixed >> 31;
+    &library.answers[(mixed % library.answers.len() as u64) as usize]
+}
+
+/// Seed for --play when --seed is not given: the current time in nanoseconds, which differs between runs
+fn clock_seed() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_nanos() as u64).unwrap_or(0)
+}
+
+/// Build the chosen strategy, looking patterns up in the matrix if there is one
+fn build_strategy(
+    kind: StrategyKind,
+    matrix: Option<Arc<PatternMatrix>>,
+    rules: Arc<dyn FeedbackRules>,
+) -> Box<dyn Strategy + Sync> {
+    match (kind, matrix) {
+        (StrategyKind::Entropy, Some(matrix)) => Box::new(EntropyStrategy::with_matrix(matrix).with_rules(rules)),
+        (StrategyKind::Entropy, None) => Box::new(EntropyStrategy::new().with_rules(rules)),
+        (StrategyKind::Minimax, Some(matrix)) => Box::new(MinimaxStrategy::with_matrix(matrix).with_rules(rules)),
+        (StrategyKind::Minimax, None) => Box::new(MinimaxStrategy::new().with_rules(rules)),
+    }
+}
+
+/// Play one game, reading feedback from input and writing suggestions to output.
+/// Suggestions are looked up in the decision tree if there is one, falling back to the solver's strategy
+/// once the game leaves the tree.
+fn run<S: Strategy>(
+    mut solver: Solver<S>,
+    tree: Option<&DecisionTree>,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<()> {
+    let mut lines = input.lines();
+    let mut tree: Option<&DecisionTree> = tree;
+    loop {
+        let looked_up: Option<&str> = tree.and_then(|tree| tree.next_guess(solver.history()));
+        if tree.is_some() && looked_up.is_none() {
+            writeln!(output, "This game is not in the decision tree, searching instead.")?;
+            // A game that has left the tree never returns to it
+            tree = None;
+        }
+        let suggestion: &str = match looked_up.or_else(|| solver.suggest()) {
+            Some(guess) => guess,
+            None => {
+                writeln!(output, "No answers in the library match that feedback.")?;
+                return Ok(());
+            }
+        };
+        writeln!(output, "{} possible answers. Try: {}", solver.candidates().len(), suggestion)?;
+        write!(output, "Feedback (e.g. gy..g, or '<guess> <feedback>' if you played something else): ")?;
+        output.flush()?;
+        let line: String = match lines.next() {
+            Some(line) => line?,
+            None => return Ok(()),
+        };
+        let line: &str = line.trim();
+        if line == "quit" || line == "q" {
+            return Ok(());
+        }
+        let (guess, feedback): (&str, &str) = match line.split_once(char::is_whitespace) {
+            Some((guess, feedback)) => (guess, feedback.trim()),
+            None => (suggestion, line),
+        };
+        // Typed words are cleaned up the same way as the word lists
+        let guess: String = guess.nfc().collect::<String>().to_lowercase();
+        let guess: &str = &guess;
+        // Suggestions come from the library, so they have its word length
+        let (expected, found): (usize, usize) = (suggestion.chars().count(), guess.chars().count());
+        if found != expected {
+            writeln!(output, "Guesses must have {} letters, got {}", expected, found)?;
+            continue;
+        }
+        let result: GuessResult = match GuessResult::from_feedback(guess, feedback) {
+            Ok(result) => result,
+            Err(error) => {
+                writeln!(output, "Could not read feedback {:?} for {}: {}", feedback, guess, error)?;
+                continue;
+            }
+        };
+        if let Err(violation) = solver.check_guess(guess) {
+            writeln!(output, "{} is not allowed in hard mode: {}", guess, violation)?;
+            continue;
+        }
+        let solved: bool = result.states().iter().all(|s| *s == LetterState::Correct);
+        solver.record(result);
+        if solved {
+            writeln!(output, "Solved in {} guesses!", solver.history().len())?;
+            return Ok(());
+        }
+    }
+}
+
+/// Play one multi-board game, reading the feedback for every unsolved board from each line of input
+fn run_boards(mut solver: MultiSolver, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    let mut lines = input.lines();
+    let mut turns: usize = 0;
+    loop {
+        let suggestion: &str = match solver.suggest() {
+            Some(guess) => guess,
+            None => {
+                writeln!(output, "No answers in the library match that feedback.")?;
+                return Ok(());
+            }
+        };
+        let remaining: Vec<String> = solver.boards().iter().map(|board| {
+            if board.is_solved() { "solved".to_string() } else { board.candidates().len().to_string() }
+        }).collect();
+        writeln!(output, "Possible answers per board: {}. Try: {}", remaining.join(", "), suggestion)?;
+        write!(output, "Feedback for each unsolved board (e.g. gy..g ..y.., prefixed with your guess if different): ")?;
+        output.flush()?;
+        let line: String = match lines.next() {
+            Some(line) => line?,
+            None => return Ok(()),
+        };
+        let line: &str = line.trim();
+        if line == "quit" || line == "q" {
+            return Ok(());
+        }
+        let unsolved: Vec<usize> = (0..solver.boards().len()).filter(|&i| !solver.boards()[i].is_solved()).collect();
+        let mut words: Vec<&str> = line.split_whitespace().collect();
+        let guess: String = match words.len() {
+            n if n == unsolved.len() + 1 => words.remove(0).nfc().collect::<String>().to_lowercase(),
+            n if n == unsolved.len() => suggestion.to_string(),
+            _ => {
+                writeln!(output, "Expected feedback for {} boards", unsolved.len())?;
+                continue;
+            }
+        };
+        let (expected, found): (usize, usize) = (suggestion.chars().count(), guess.chars().count());
+        if found != expected {
+            writeln!(output, "Guesses must have {} letters, got {}", expected, found)?;
+            continue;
+        }
+        let results: Result<Vec<GuessResult>, FeedbackError> = words.iter()
+            .map(|feedback| GuessResult::from_feedback(&guess, feedback))
+            .collect();
+        let results: Vec<GuessResult> = match results {
+            Ok(results) => results,
+            Err(error) => {
+                writeln!(output, "Could not read feedback {:?} for {}: {}", words.join(" "), guess, error)?;
+                continue;
+            }
+        };
+        for (board, result) in unsolved.into_iter().zip(results) {
+            solver.record(board, result);
+        }
+        turns += 1;
+        if solver.is_solved() {
+            writeln!(output, "Solved all {} boards in {} guesses!", solver.boards().len(), turns)?;
+            return Ok(());
+        }
+    }
+}
+
+/// Play a game against its secret answer, reading guesses from input and writing feedback to output
+fn run_game(mut game: Game, renderer: &dyn Renderer, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    let mut lines = input.lines();
+    while game.status() == GameStatus::InProgress {
+        write!(output, "Guess ({} left): ", game.turns_left())?;
+        output.flush()?;
+        let line: String = match lines.next() {
+            Some(line) => line?,
+            None => return Ok(()),
+        };
+        let guess: String = line.trim().nfc().collect::<String>().to_lowercase();
+        if guess == "quit" || guess == "q" {
+            return Ok(());
+        }
+        match game.guess(&guess) {
+            Ok(result) => writeln!(output, "{}", renderer.render(result))?,
+            Err(error) => writeln!(output, "{}", error)?,
+        }
+    }
+    match game.status() {
+        GameStatus::Won { turns } => writeln!(output, "You won in {} guesses!", turns),
+        _ => writeln!(output, "Out of guesses. The answer was {}", game.answer()),
+    }
+}
+
+/// Play against an adversarial host, reading guesses from input and writing its feedback to output
+fn run_absurdle(
+    library: &Library,
+    mut host: AdversarialHost,
+    renderer: &dyn Renderer,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<()> {
+    let mut lines = input.lines();
+    loop {
+        write!(output, "Guess: ")?;
+        output.flush()?;
+        let line: String = match lines.next() {
+            Some(line) => line?,
+            None => return Ok(()),
+        };
+        let guess: String = line.trim().nfc().collect::<String>().to_lowercase();
+        if guess == "quit" || guess == "q" {
+            return Ok(());
+        }
+        if !library.is_guess(&guess) {
+            writeln!(output, "{} is not in the word list", guess)?;
+            continue;
+        }
+        let feedback: String = renderer.render(host.respond(&guess));
+        if host.is_won() {
+            writeln!(output, "{}\nYou won in {} guesses!", feedback, host.history().len())?;
+            return Ok(());
+        }
+        writeln!(output, "{} {} possible answers left", feedback, host.candidates().len())?;
+    }
+}
+
+/// Print the guess distribution of a simulation
+fn print_report(report: &SimulationReport) {
+    let widest: usize = report.distribution.iter().copied().max().unwrap_or(0).max(1);
+    for (i, &count) in report.distribution.iter().enumerate() {
+        println!("{:>2} | {:<40} {}", i + 1, "#".repeat(count * 40 / widest), count);
+    }
+    println!(" X | {}", report.failures.len());
+    println!("Solved {}/{} games, mean {:.4} guesses", report.solved(), report.games(), report.mean());
+    if let Some(turns) = report.worst_turns() {
+        println!("Hardest answers ({} guesses): {}", turns, report.worst.join(", "));
+    }
+    if !report.failures.is_empty() {
+        println!("Failed answers: {}", report.failures.join(", "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    // Local crate imports
+    use super::*;
+    use rust_wordle_solver::pattern::Pattern;
+
+    #[test]
+    fn test_run_solves_a_game() {
+        let words: Vec<String> = ["crane", "slate", "trace", "plumb"].iter().map(|w| w.to_string()).collect();
+        let library: Library = Library::new(words.clone(), words).unwrap();
+        let answer: &str = "plumb";
+        let mut transcript: Vec<u8> = Vec::new();
+        // A mistyped guess is rejected instead of emptying the candidates
+        let mut input: String = "cranes GYBBBB\n".to_string();
+        let mut solver: Solver<EntropyStrategy> = Solver::new(&library, EntropyStrategy::new());
+        while let Some(guess) = solver.suggest() {
+            let result: GuessResult = GuessResult::evaluate_guess(guess, answer);
+            input.push_str(&result.to_string());
+            input.push('\n');
+            if guess == answer {
+                break;
+            }
+            solver.record(result);
+        }
+        let solver: Solver<EntropyStrategy> = Solver::new(&library, EntropyStrategy::new());
+        run(solver, None, input.as_bytes(), &mut transcript).unwrap();
+        let transcript: String = String::from_utf8(transcript).unwrap();
+        assert!(transcript.contains("Guesses must have 5 letters, got 6"));
+        assert!(transcript.contains("Solved"));
+    }
+
+    #[test]
+    fn test_run_solves_other_lengths() {
+        for words in [["bark", "lake", "mint", "cord"], ["planet", "stream", "bright", "flower"]] {
+            let words: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+            let library: Library = Library::new(words.clone(), words.clone()).unwrap();
+            let answer: &str = &words[3];
+            let mut input: String = String::new();
+            let mut solver: Solver<MinimaxStrategy> = Solver::new(&library, MinimaxStrategy::new());
+            while let Some(guess) = solver.suggest() {
+                let result: GuessResult = GuessResult::evaluate_guess(guess, answer);
+                input.push_str(&result.to_string());
+                input.push('\n');
+                if guess == answer {
+                    break;
+                }
+                solver.record(result);
+            }
+            let mut transcript: Vec<u8> = Vec::new();
+            let solver: Solver<MinimaxStrategy> = Solver::new(&library, MinimaxStrategy::new());
+            run(solver, None, input.as_bytes(), &mut transcript).unwrap();
+            assert!(String::from_utf8(transcript).unwrap().contains("Solved"));
+        }
+    }
+
+    #[test]
+    fn test_run_boards_solves_a_dordle() {
+        let words: Vec<String> = ["crane", "slate", "trace", "plumb"].iter().map(|w| w.to_string()).collect();
+        let library: Library = Library::new(words.clone(), words).unwrap();
+        let answers: [&str; 2] = ["plumb", "trace"];
+        let mut input: String = "cranes BBBBBB BBBBBB\n".to_string();
+        let mut solver: MultiSolver = MultiSolver::new(&library, 2);
+        while let Some(guess) = solver.suggest() {
+            let mut feedback: Vec<String> = Vec::new();
+            for (board, answer) in answers.iter().enumerate() {
+                if !solver.boards()[board].is_solved() {
+                    let result: GuessResult = GuessResult::evaluate_guess(guess, answer);
+                    feedback.push(result.to_string());
+                    solver.record(board, result);
+                }
+            }
+            input.push_str(&feedback.join(" "));
+            input.push('\n');
+        }
+        let mut transcript: Vec<u8> = Vec::new();
+        run_boards(MultiSolver::new(&library, 2), input.as_bytes(), &mut transcript).unwrap();
+        let transcript: String = String::from_utf8(transcript).unwrap();
+        assert!(transcript.contains("Guesses must have 5 letters, got 6"));
+        assert!(transcript.contains("Solved all 2 boards"));
+    }
+
+    #[test]
+    fn test_seeded_answers_can_be_replayed() {
+        let args = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+        let Ok(options) = args(&["guesses.txt", "answers.txt", "--seed", "42"]) else { panic!("--seed should parse") };
+        assert!(options.play);
+        assert_eq!(options.seed, Some(42));
+        assert!(args(&["guesses.txt", "answers.txt", "--seed", "soon"]).is_err());
+
+        let words: Vec<String> = ["crane", "slate", "trace", "react", "cater", "plumb", "brick", "eerie"]
+            .iter().map(|w| w.to_string()).collect();
+        let library: Library = Library::new(words.clone(), words).unwrap();
+        for seed in 0..20 {
+            assert_eq!(choose_answer(&library, seed), choose_answer(&library, seed));
+            assert!(library.answers.iter().any(|answer| answer == choose_answer(&library, seed)));
+        }
+        // Consecutive seeds should not all land on the same answer
+        let picked: Vec<&str> = (0..20).map(|seed| choose_answer(&library, seed)).collect();
+        assert!(picked.iter().any(|&answer| answer != picked[0]));
+    }
+
+    #[test]
+    fn test_run_game_until_over() {
+        let words: Vec<String> = ["crane", "slate", "trace", "plumb"].iter().map(|w| w.to_string()).collect();
+        let library: Library = Library::new(words.clone(), words).unwrap();
+        let mut transcript: Vec<u8> = Vec::new();
+        let game: Game = Game::new(&library, "trace").unwrap();
+        run_game(game, &AsciiRenderer, "zzzzz\ncrane\nTRACE\n".as_bytes(), &mut transcript).unwrap();
+        let transcript: String = String::from_utf8(transcript).unwrap();
+        assert!(transcript.contains("zzzzz is not in the word list"));
+        assert!(transcript.contains("YGG.G\n"));
+        assert!(transcript.contains("You won in 2 guesses!"));
+        let mut transcript: Vec<u8> = Vec::new();
+        let game: Game = Game::new(&library, "plumb").unwrap().with_max_turns(1);
+        run_game(game, &EmojiRenderer::dark(), "crane\n".as_bytes(), &mut transcript).unwrap();
+        assert!(String::from_utf8(transcript).unwrap().contains("The answer was plumb"));
+    }
+
+    #[test]
+    fn test_run_absurdle_until_won() {
+        let words: Vec<String> = ["plumb", "eerie"].iter().map(|w| w.to_string()).collect();
+        let library: Library = Library::new(words.clone(), words).unwrap();
+        let mut transcript: Vec<u8> = Vec::new();
+        let host: AdversarialHost = AdversarialHost::new(&library);
+        let input: &[u8] = "zzzzz\nPLUMB\neerie\n".as_bytes();
+        run_absurdle(&library, host, &EmojiRenderer::dark(), input, &mut transcript).unwrap();
+        let transcript: String = String::from_utf8(transcript).unwrap();
+        assert!(transcript.contains("zzzzz is not in the word list"));
+        assert!(transcript.contains("1 possible answers left"));
+        assert!(transcript.contains("You won in 2 guesses!"));
+    }
+
+    #[test]
+    fn test_run_plays_from_tree() {
+        let words: Vec<String> = ["crane", "slate", "trace", "plumb"].iter().map(|w| w.to_string()).collect();
+        let library: Library = Library::new(words.clone(), words).unwrap();
+        let mut tree: DecisionTree = DecisionTree::leaf("plumb");
+        tree.branches.insert(Pattern::evaluate("plumb", "slate"), DecisionTree::leaf("slate"));
+        let mut transcript: Vec<u8> = Vec::new();
+        let solver: Solver<EntropyStrategy> = Solver::new(&library, EntropyStrategy::new());
+        run(solver, Some(&tree), "bgbbb\nggggg\n".as_bytes(), &mut transcript).unwrap();
+        let transcript: String = String::from_utf8(transcript).unwrap();
+        assert!(transcript.contains("Try: plumb"));
+        assert!(transcript.contains("Solved in 2 guesses"));
+    }
+
+    #[test]
+    fn test_run_leaves_tree_once() {
+        let words: Vec<String> = ["crane", "slate", "trace", "plumb"].iter().map(|w| w.to_string()).collect();
+        let library: Library = Library::new(words.clone(), words).unwrap();
+        let tree: DecisionTree = DecisionTree::leaf("plumb");
+        let mut transcript: Vec<u8> = Vec::new();
+        let solver: Solver<EntropyStrategy> = Solver::new(&library, EntropyStrategy::new());
+        run(solver, Some(&tree), "bbbbb\ncrane yggbg\ntrace ggggg\n".as_bytes(), &mut transcript).unwrap();
+        let transcript: String = String::from_utf8(transcript).unwrap();
+        assert_eq!(transcript.matches("not in the decision tree").count(), 1);
+        assert!(transcript.contains("Solved in 3 guesses"));
+    }
+
+}
@@ -0,0 +1,449 @@
+//! Precomputed feedback patterns.
+//!
+//! Evaluates every guess in a library against every answer once and stores the resulting patterns
+//! so they can be looked up by index. Matrices can be cached on disk and memory-mapped on reload.
+
+// Standard library imports
+use std::collections::HashMap;
+use std::fs;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::thread;
+
+// External crate imports
+use memmap2::Mmap;
+
+// Local crate imports
+use crate::index_words;
+use crate::pattern;
+use crate::pattern::Pattern;
+use crate::rules::FeedbackRules;
+use crate::rules::NytRules;
+use crate::Library;
+
+/// Magic bytes at the start of a cached pattern matrix
+const MAGIC: &[u8; 4] = b"WSPM";
+
+/// Version of the cache file layout
+const VERSION: u32 = 1;
+
+/// Size of the cache file header in bytes
+const HEADER_LENGTH: usize = 32;
+
+/// Counter that keeps the temporary files of one process apart
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Pattern for every guess/answer pair in a library
+pub struct PatternMatrix {
+    key: u64,
+    word_length: usize,
+    guess_count: usize,
+    answer_count: usize,
+    width: usize,
+    guess_indices: HashMap<String, usize>,
+    answer_indices: HashMap<String, usize>,
+    data: Storage,
+}
+
+/// Backing bytes of a pattern matrix
+enum Storage {
+    Owned(Vec<u8>),
+    Mapped(Mmap),
+}
+
+impl Storage {
+
+    /// Pattern bytes, excluding any file header
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Storage::Owned(bytes) => bytes,
+            Storage::Mapped(map) => &map[HEADER_LENGTH..],
+        }
+    }
+
+}
+
+impl PatternMatrix {
+
+    /// Evaluate every guess against every answer using the New York Times rules
+    pub fn compute(library: &Library) -> PatternMatrix {
+        PatternMatrix::compute_with(library, &NytRules)
+    }
+
+    /// Evaluate every guess against every answer, splitting the guesses across all available threads
+    pub fn compute_with(library: &Library, rules: &dyn FeedbackRules) -> PatternMatrix {
+        let width: usize = entry_width(library.word_length);
+        let row_length: usize = library.answers.len() * width;
+        let mut bytes: Vec<u8> = vec![0; library.guesses.len() * row_length];
+        if row_length > 0 {
+            let threads: usize = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+            let rows_per_thread: usize = library.guesses.len().div_ceil(threads).max(1);
+            thread::scope(|scope| {
+                let chunks = bytes.chunks_mut(rows_per_thread * row_length);
+                for (chunk, guesses) in chunks.zip(library.guesses.chunks(rows_per_thread)) {
+                    scope.spawn(move || {
+                        for (row, guess) in chunk.chunks_mut(row_length).zip(guesses) {
+                            for (entry, answer) in row.chunks_mut(width).zip(&library.answers) {
+                                write_entry(entry, Pattern::evaluate_with(rules, guess, answer));
+                            }
+                        }
+                    });
+                }
+            });
+        }
+        PatternMatrix::with_storage(library, library_key(library, rules), Storage::Owned(bytes))
+    }
+
+    /// Load a New York Times rules matrix from the cache directory, or compute and cache it
+    pub fn load_or_compute(library: &Library, cache_dir: &Path) -> io::Result<PatternMatrix> {
+        PatternMatrix::load_or_compute_with(library, &NytRules, cache_dir)
+    }
+
+    /// Load a matrix from the cache directory if one exists for this library and rule set, otherwise
+    /// compute it and write it to the cache directory
+    pub fn load_or_compute_with(
+        library: &Library,
+        rules: &dyn FeedbackRules,
+        cache_dir: &Path,
+    ) -> io::Result<PatternMatrix> {
+        let path: PathBuf = PatternMatrix::cache_path(library, rules, cache_dir);
+        if path.exists() && let Ok(matrix) = PatternMatrix::load(library, rules, &path) {
+            return Ok(matrix);
+        }
+        let matrix: PatternMatrix = PatternMatrix::compute_with(library, rules);
+        fs::create_dir_all(cache_dir)?;
+        matrix.save(&path)?;
+        Ok(matrix)
+    }
+
+    /// Path of the cache file for a library and rule set within a cache directory
+    pub fn cache_path(library: &Library, rules: &dyn FeedbackRules, cache_dir: &Path) -> PathBuf {
+        cache_dir.join(format!("patterns-{:016x}.bin", library_key(library, rules)))
+    }
+
+    /// Memory-map a cached matrix, checking that it was computed from the same library and rule set
+    pub fn load(library: &Library, rules: &dyn FeedbackRules, path: &Path) -> io::Result<PatternMatrix> {
+        let key: u64 = library_key(library, rules);
+        let file: File = File::open(path)?;
+        // SAFETY: the cache file is only ever replaced by renaming a fully written file over it,
+        // so the mapped contents are not modified while the map is alive
+        let map: Mmap = unsafe { Mmap::map(&file)? };
+        let header: Header = Header::parse(&map)?;
+        let expected: Header = Header::for_library(library, key);
+        if header != expected {
+            return Err(invalid_data(format!("Pattern matrix {} does not match the library", path.display())));
+        }
+        let data_length: usize = header.guess_count * header.answer_count * header.width;
+        if map.len() != HEADER_LENGTH + data_length {
+            return Err(invalid_data(format!("Pattern matrix {} is truncated", path.display())));
+        }
+        Ok(PatternMatrix::with_storage(library, key, Storage::Mapped(map)))
+    }
+
+    /// Write the matrix to a file, replacing it atomically
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let header: Header = Header {
+            key: self.key,
+            word_length: self.word_length,
+            guess_count: self.guess_count,
+            answer_count: self.answer_count,
+            width: self.width,
+        };
+        let (temp_path, mut file): (PathBuf, File) = create_temp_file(path)?;
+        let written: io::Result<()> = file.write_all(&header.to_bytes())
+            .and_then(|_| file.write_all(self.data.bytes()))
+            .and_then(|_| file.sync_all())
+            .and_then(|_| fs::rename(&temp_path, path));
+        if written.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+        written
+    }
+
+    /// Pattern for a guess and answer, by their positions in the library
+    pub fn get(&self, guess_index: usize, answer_index: usize) -> Pattern {
+        let offset: usize = (guess_index * self.answer_count + answer_index) * self.width;
+        read_entry(&self.data.bytes()[offset..offset + self.width])
+    }
+
+    /// Pattern for a guess and answer, by word. Returns None if either word is not in the matrix.
+    pub fn lookup(&self, guess: &str, answer: &str) -> Option<Pattern> {
+        Some(self.get(self.guess_index(guess)?, self.answer_index(answer)?))
+    }
+
+    /// Position of a word in the guesses the matrix was computed from
+    pub fn guess_index(&self, word: &str) -> Option<usize> {
+        self.guess_indices.get(word).copied()
+    }
+
+    /// Position of a word in the answers the matrix was computed from
+    pub fn answer_index(&self, word: &str) -> Option<usize> {
+        self.answer_indices.get(word).copied()
+    }
+
+    /// Positions of several answers. Returns None if any of them is not in the matrix.
+    pub fn answer_indices(&self, words: &[&str]) -> Option<Vec<usize>> {
+        words.iter().map(|w| self.answer_index(w)).collect()
+    }
+
+    /// Sizes of the non-empty pattern buckets a guess splits the answers into, in pattern order
+    pub fn bucket_sizes(&self, guess_index: usize, answer_indices: &[usize]) -> Vec<usize> {
+        pattern::sizes_of(self.word_length, answer_indices.iter().map(|&answer| self.get(guess_index, answer)))
+    }
+
+    /// Number of guesses in the matrix
+    pub fn guess_count(&self) -> usize {
+        self.guess_count
+    }
+
+    /// Number of answers in the matrix
+    pub fn answer_count(&self) -> usize {
+        self.answer_count
+    }
+
+    /// Whether the matrix is backed by a memory-mapped cache file
+    pub fn is_mapped(&self) -> bool {
+        matches!(self.data, Storage::Mapped(_))
+    }
+
+    /// Wrap pattern bytes for a library
+    fn with_storage(library: &Library, key: u64, data: Storage) -> PatternMatrix {
+        PatternMatrix {
+            key,
+            word_length: library.word_length,
+            guess_count: library.guesses.len(),
+            answer_count: library.answers.len(),
+            width: entry_width(library.word_length),
+            guess_indices: index_words(&library.guesses),
+            answer_indices: index_words(&library.answers),
+            data,
+        }
+    }
+
+}
+
+/// Fixed-size header at the start of a cache file
+#[derive(PartialEq)]
+struct Header {
+    key: u64,
+    word_length: usize,
+    guess_count: usize,
+    answer_count: usize,
+    width: usize,
+}
+
+impl Header {
+
+    /// Header a cache file for this library should have
+    fn for_library(library: &Library, key: u64) -> Header {
+        Header {
+            key,
+            word_length: library.word_length,
+            guess_count: library.guesses.len(),
+            answer_count: library.answers.len(),
+            width: entry_width(library.word_length),
+        }
+    }
+
+    /// Read a header from the start of a cache file
+    fn parse(bytes: &[u8]) -> io::Result<Header> {
+        if bytes.len() < HEADER_LENGTH || &bytes[0..4] != MAGIC {
+            return Err(invalid_data("Not a pattern matrix file".to_string()));
+        }
+        let read_u32 = |at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap()) as usize;
+        if read_u32(4) != VERSION as usize {
+            return Err(invalid_data(format!("Unsupported pattern matrix version {}", read_u32(4))));
+        }
+        Ok(Header {
+            key: u64::from_le_bytes(bytes[8..16].try_into().unwrap()),
+            guess_count: read_u32(16),
+            answer_count: read_u32(20),
+            word_length: read_u32(24),
+            width: read_u32(28),
+        })
+    }
+
+    /// Serialize the header
+    fn to_bytes(&self) -> [u8; HEADER_LENGTH] {
+        let mut bytes: [u8; HEADER_LENGTH] = [0; HEADER_LENGTH];
+        bytes[0..4].copy_from_slice(MAGIC);
+        bytes[4..8].copy_from_slice(&VERSION.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.key.to_le_bytes());
+        bytes[16..20].copy_from_slice(&(self.guess_count as u32).to_le_bytes());
+        bytes[20..24].copy_from_slice(&(self.answer_count as u32).to_le_bytes());
+        bytes[24..28].copy_from_slice(&(self.word_length as u32).to_le_bytes());
+        bytes[28..32].copy_from_slice(&(self.width as u32).to_le_bytes());
+        bytes
+    }
+
+}
+
+/// Stable hash of the word lists of a library and the name of a rule set, used to key cache files.
+/// Uses 64-bit FNV-1a so the key does not change between builds.
+pub fn library_key(library: &Library, rules: &dyn FeedbackRules) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    let mut feed = |bytes: &[u8]| {
+        for &byte in bytes {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    };
+    feed(rules.name().as_bytes());
+    feed(b"\0");
+    feed(&(library.word_length as u64).to_le_bytes());
+    for words in [&library.guesses, &library.answers] {
+        for word in words {
+            feed(word.as_bytes());
+            feed(b"\n");
+        }
+        feed(b"\0");
+    }
+    hash
+}
+
+/// Bytes needed to store one pattern for words of the given length
+fn entry_width(word_length: usize) -> usize {
+    match Pattern::count(word_length) {
+        count if count <= u8::MAX as usize + 1 => 1,
+        count if count <= u16::MAX as usize + 1 => 2,
+        _ => 4,
+    }
+}
+
+/// Store a pattern in a little-endian entry
+fn write_entry(entry: &mut [u8], pattern: Pattern) {
+    entry.copy_from_slice(&pattern.code().to_le_bytes()[..entry.len()]);
+}
+
+/// Read a pattern from a little-endian entry
+fn read_entry(entry: &[u8]) -> Pattern {
+    let mut bytes: [u8; 4] = [0; 4];
+    bytes[..entry.len()].copy_from_slice(entry);
+    Pattern::from_code(u32::from_le_bytes(bytes))
+}
+
+/// Build an error for a malformed cache file
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Create a new temporary file next to a path, named after the process and a counter so that no other
+/// writer can be using it. Only a file written in full by its creator is ever renamed over the path.
+fn create_temp_file(path: &Path) -> io::Result<(PathBuf, File)> {
+    loop {
+        let nonce: u64 = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let temp_path: PathBuf = path.with_extension(format!("{}.{}.tmp", process::id(), nonce));
+        match OpenOptions::new().write(true).create_new(true).open(&temp_path) {
+            Ok(file) => return Ok((temp_path, file)),
+            // Left behind by an earlier process with the same id
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    // Local crate imports
+    use super::*;
+    use crate::rules::NaiveRules;
+    use crate::tests::create_word_library;
+
+    /// Answers of the library the tests use
+    const ANSWERS: [&str; 6] = ["crane", "slate", "trace", "eerie", "ether", "speed"];
+
+    /// Words the tests can guess that are never answers
+    const GUESS_ONLY: [&str; 3] = ["abide", "zzzzz", "puppy"];
+
+    /// Create an empty directory for cache files
+    fn create_cache_dir(name: &str) -> PathBuf {
+        let dir: PathBuf = std::env::temp_dir().join(format!("wordle-matrix-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    /// Check every entry of a matrix against direct evaluation
+    fn assert_matrix_matches(matrix: &PatternMatrix, library: &Library) {
+        for (g, guess) in library.guesses.iter().enumerate() {
+            for (a, answer) in library.answers.iter().enumerate() {
+                assert_eq!(matrix.get(g, a), Pattern::evaluate(guess, answer), "{} vs {}", guess, answer);
+            }
+        }
+    }
+
+    #[test]
+    fn test_compute_matches_evaluation() {
+        let library: Library = create_word_library(&ANSWERS, &GUESS_ONLY);
+        let matrix: PatternMatrix = PatternMatrix::compute(&library);
+        assert_matrix_matches(&matrix, &library);
+        assert_eq!(matrix.lookup("puppy", "speed"), Some(Pattern::evaluate("puppy", "speed")));
+        assert_eq!(matrix.lookup("speed", "puppy"), None);
+    }
+
+    #[test]
+    fn test_cache_round_trip() {
+        let library: Library = create_word_library(&ANSWERS, &GUESS_ONLY);
+        let cache_dir: PathBuf = create_cache_dir("round-trip");
+        let computed: PatternMatrix = PatternMatrix::load_or_compute(&library, &cache_dir).unwrap();
+        assert!(!computed.is_mapped());
+        let loaded: PatternMatrix = PatternMatrix::load_or_compute(&library, &cache_dir).unwrap();
+        assert!(loaded.is_mapped());
+        assert_matrix_matches(&loaded, &library);
+        fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_saves_use_separate_temp_files() {
+        let library: Library = create_word_library(&ANSWERS, &GUESS_ONLY);
+        let matrix: PatternMatrix = PatternMatrix::compute(&library);
+        let cache_dir: PathBuf = create_cache_dir("concurrent");
+        fs::create_dir_all(&cache_dir).unwrap();
+        let path: PathBuf = PatternMatrix::cache_path(&library, &NytRules, &cache_dir);
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| matrix.save(&path).unwrap());
+            }
+        });
+        assert_matrix_matches(&PatternMatrix::load(&library, &NytRules, &path).unwrap(), &library);
+        assert_eq!(fs::read_dir(&cache_dir).unwrap().count(), 1, "Temporary files were left behind");
+        fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_other_word_lengths() {
+        for (word_length, width) in [(4, 1), (6, 2), (7, 2), (11, 4)] {
+            let library: Library = crate::tests::create_generated_library(word_length, 30);
+            assert_eq!(entry_width(word_length), width);
+            let cache_dir: PathBuf = create_cache_dir(&format!("length-{}", word_length));
+            PatternMatrix::load_or_compute(&library, &cache_dir).unwrap();
+            let loaded: PatternMatrix = PatternMatrix::load_or_compute(&library, &cache_dir).unwrap();
+            assert!(loaded.is_mapped());
+            assert_matrix_matches(&loaded, &library);
+            fs::remove_dir_all(&cache_dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_cache_is_keyed_by_word_lists() {
+        let library: Library = create_word_library(&ANSWERS, &GUESS_ONLY);
+        let mut other: Library = create_word_library(&ANSWERS, &GUESS_ONLY);
+        other.answers.pop();
+        assert_ne!(library_key(&library, &NytRules), library_key(&other, &NytRules));
+        assert_ne!(library_key(&library, &NytRules), library_key(&library, &NaiveRules));
+        let cache_dir: PathBuf = create_cache_dir("keyed");
+        PatternMatrix::load_or_compute(&library, &cache_dir).unwrap();
+        let stale: PathBuf = PatternMatrix::cache_path(&library, &NytRules, &cache_dir);
+        assert!(PatternMatrix::load(&other, &NytRules, &stale).is_err());
+        assert!(PatternMatrix::load(&library, &NaiveRules, &stale).is_err());
+        fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+}
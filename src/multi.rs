@@ -0,0 +1,216 @@
+//! Multi-board solving.
+//!
+//! Variants such as Dordle, Quordle and Octordle score every guess against several hidden answers at
+//! once. Each board keeps its own candidates and history, and guesses are chosen by the information
+//! they are expected to reveal, summed over the boards that are still unsolved.
+
+// Standard library imports
+use std::sync::Arc;
+
+// Local crate imports
+use crate::filter;
+use crate::matrix::PatternMatrix;
+use crate::rules::FeedbackRules;
+use crate::rules::NytRules;
+use crate::solver;
+use crate::GuessResult;
+use crate::LetterState;
+use crate::Library;
+
+/// One board of a multi-board game
+pub struct Board<'a> {
+    candidates: Vec<&'a str>,
+    history: Vec<GuessResult>,
+    solved: bool,
+}
+
+impl<'a> Board<'a> {
+
+    /// Answers that are still possible on this board
+    pub fn candidates(&self) -> &[&'a str] {
+        &self.candidates
+    }
+
+    /// Guess results recorded on this board
+    pub fn history(&self) -> &[GuessResult] {
+        &self.history
+    }
+
+    /// Whether the board's answer has been guessed
+    pub fn is_solved(&self) -> bool {
+        self.solved
+    }
+
+}
+
+/// Tracks the boards of a multi-board game and suggests guesses that help the unsolved boards most
+pub struct MultiSolver<'a> {
+    rules: Arc<dyn FeedbackRules>,
+    matrix: Option<Arc<PatternMatrix>>,
+    guesses: Vec<&'a str>,
+    boards: Vec<Board<'a>>,
+}
+
+impl<'a> MultiSolver<'a> {
+
+    /// Create a solver for a fresh game with the given number of boards, each starting with every answer
+    pub fn new(library: &'a Library, board_count: usize) -> MultiSolver<'a> {
+        let boards: Vec<Board<'a>> = (0..board_count).map(|_| Board {
+            candidates: library.answers.iter().map(|w| w.as_str()).collect(),
+            history: Vec::new(),
+            solved: false,
+        }).collect();
+        MultiSolver {
+            rules: Arc::new(NytRules),
+            matrix: None,
+            guesses: library.guesses.iter().map(|w| w.as_str()).collect(),
+            boards,
+        }
+    }
+
+    /// Score guesses and narrow candidates with a different rule set
+    pub fn with_rules(self, rules: Arc<dyn FeedbackRules>) -> MultiSolver<'a> {
+        MultiSolver { rules, ..self }
+    }
+
+    /// Look patterns up in a precomputed matrix, which should have been computed with the same rules
+    pub fn with_matrix(self, matrix: Arc<PatternMatrix>) -> MultiSolver<'a> {
+        MultiSolver { matrix: Some(matrix), ..self }
+    }
+
+    /// Every board, in order
+    pub fn boards(&self) -> &[Board<'a>] {
+        &self.boards
+    }
+
+    /// Whether every board has been solved
+    pub fn is_solved(&self) -> bool {
+        self.boards.iter().all(|board| board.solved)
+    }
+
+    /// Score every guess by the expected information it reveals, summed over the unsolved boards, best first.
+    /// Ties are broken in favour of guesses that could be the answer on some board, then by pool order.
+    pub fn rank_guesses(&self) -> Vec<(&'a str, f64)> {
+        self.rank(&self.guesses)
+    }
+
+    /// Suggest the next guess.
+    /// If some unsolved board is down to one candidate, that candidate is guessed, since it is sure to solve
+    /// a board; among several such candidates, the most informative one for the other boards is chosen.
+    /// Returns None if every board is solved or some board has no candidates left.
+    pub fn suggest(&self) -> Option<&'a str> {
+        let unsolved: Vec<&Board<'a>> = self.boards.iter().filter(|board| !board.solved).collect();
+        if unsolved.is_empty() || unsolved.iter().any(|board| board.candidates.is_empty()) {
+            return None;
+        }
+        let certain: Vec<&'a str> = unsolved.iter()
+            .filter(|board| board.candidates.len() == 1)
+            .map(|board| board.candidates[0])
+            .collect();
+        let pool: &[&'a str] = if certain.is_empty() { &self.guesses } else { &certain };
+        self.rank(pool).first().map(|(guess, _)| *guess)
+    }
+
+    /// Record the feedback a guess produced on one board and narrow that board's candidates
+    pub fn record(&mut self, board: usize, result: GuessResult) {
+        let board: &mut Board<'a> = &mut self.boards[board];
+        board.solved = result.states().iter().all(|state| *state == LetterState::Correct);
+        board.candidates = filter::narrow_with(self.rules.as_ref(), &board.candidates, &result);
+        board.history.push(result);
+    }
+
+    /// Play against hidden answers, one per board.
+    /// Returns the number of guesses needed to solve every board, or None if they were not all solved
+    /// within the turn limit.
+    pub fn play(&mut self, answers: &[&str], max_turns: usize) -> Option<usize> {
+        if answers.len() != self.boards.len() {
+            panic!("Expected one answer per board: {} answers for {} boards", answers.len(), self.boards.len());
+        }
+        for turn in 1..=max_turns {
+            let guess: &str = self.suggest()?;
+            for (board, answer) in answers.iter().enumerate() {
+                if !self.boards[board].solved {
+                    self.record(board, GuessResult::evaluate_guess_with(self.rules.as_ref(), guess, answer));
+                }
+            }
+            if self.is_solved() {
+                return Some(turn);
+            }
+        }
+        None
+    }
+
+    /// Score a pool of guesses by summed entropy over the unsolved boards, best first
+    fn rank(&self, guesses: &[&'a str]) -> Vec<(&'a str, f64)> {
+        let mut scores: Vec<f64> = vec![0.0; guesses.len()];
+        let mut is_candidate: Vec<bool> = vec![false; guesses.len()];
+        for board in self.boards.iter().filter(|board| !board.solved) {
+            let mut index: usize = 0;
+            solver::for_each_bucket_sizes(
+                self.rules.as_ref(), self.matrix.as_deref(), guesses, &board.candidates,
+                |guess, sizes| {
+                    scores[index] += solver::entropy_from_counts(sizes, board.candidates.len());
+                    is_candidate[index] |= board.candidates.contains(&guess);
+                    index += 1;
+                },
+            );
+        }
+        let mut ranked: Vec<(&'a str, f64, bool)> = guesses.iter().copied()
+            .zip(scores)
+            .zip(is_candidate)
+            .map(|((guess, score), is_candidate)| (guess, score, is_candidate))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| solver::prefer_candidates(a.2, b.2)));
+        ranked.into_iter().map(|(guess, score, _)| (guess, score)).collect()
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    // Local crate imports
+    use super::*;
+    use crate::tests::create_word_library;
+    use crate::tests::REPEATED_LETTER_WORDS;
+
+    #[test]
+    fn test_quordle_games_are_solved() {
+        let library: Library = create_word_library(&REPEATED_LETTER_WORDS, &["zzzzz"]);
+        let answers: Vec<&str> = library.answers.iter().map(|w| w.as_str()).collect();
+        for window in answers.windows(4) {
+            let mut solver: MultiSolver = MultiSolver::new(&library, 4);
+            let turns: usize = solver.play(window, 9).expect("Quordle game should be solved");
+            assert!(turns >= 4);
+            assert!(solver.boards().iter().all(|board| board.candidates().len() == 1));
+        }
+    }
+
+    #[test]
+    fn test_scores_sum_over_unsolved_boards() {
+        let library: Library = create_word_library(&REPEATED_LETTER_WORDS, &["zzzzz"]);
+        let mut solver: MultiSolver = MultiSolver::new(&library, 3);
+        solver.record(0, GuessResult::evaluate_guess("plumb", "plumb"));
+        solver.record(1, GuessResult::evaluate_guess("plumb", "crane"));
+        solver.record(2, GuessResult::evaluate_guess("plumb", "brick"));
+        for (guess, score) in solver.rank_guesses() {
+            let expected: f64 = solver::entropy(guess, solver.boards()[1].candidates())
+                + solver::entropy(guess, solver.boards()[2].candidates());
+            assert!((score - expected).abs() < 1e-9, "Wrong score for {}", guess);
+        }
+    }
+
+    #[test]
+    fn test_certain_answers_are_guessed_first() {
+        let library: Library = create_word_library(&REPEATED_LETTER_WORDS, &["zzzzz"]);
+        let mut solver: MultiSolver = MultiSolver::new(&library, 2);
+        solver.record(0, GuessResult::evaluate_guess("crane", "eerie"));
+        solver.record(1, GuessResult::evaluate_guess("crane", "trace"));
+        assert_eq!(solver.boards()[0].candidates(), ["eerie"]);
+        assert_eq!(solver.suggest(), Some("eerie"));
+        solver.record(0, GuessResult::evaluate_guess("eerie", "eerie"));
+        assert!(solver.boards()[0].is_solved());
+        assert!(!solver.is_solved());
+    }
+
+}
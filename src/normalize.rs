@@ -0,0 +1,215 @@
+//! Word list normalization.
+//!
+//! Cleans up the raw lines of a word list before they are loaded into a library, and reports what
+//! was changed or rejected along the way.
+
+// Standard library imports
+use std::collections::HashMap;
+
+// External crate imports
+use unicode_normalization::UnicodeNormalization;
+
+// Local crate imports
+use crate::alphabet::Alphabet;
+
+/// Options controlling how the lines of a word list are turned into words
+#[derive(Clone, Debug)]
+pub struct Normalization {
+
+    /// Remove leading and trailing whitespace, including the carriage return of CRLF line endings
+    pub trim: bool,
+
+    /// Compose accented letters into single characters (Unicode NFC), so that e.g. 'n' followed by a
+    /// combining tilde becomes 'ñ' and counts as one letter
+    pub compose: bool,
+
+    /// Convert words to lowercase
+    pub lowercase: bool,
+
+    /// Ignore lines that are empty (after trimming)
+    pub skip_blank: bool,
+
+    /// Ignore everything from this character to the end of the line
+    pub comment_prefix: Option<char>,
+
+    /// Keep only the first occurrence of each word
+    pub dedupe: bool,
+
+    /// Reject words containing letters outside this alphabet
+    pub alphabet: Option<Alphabet>,
+}
+
+impl Default for Normalization {
+
+    /// Trim, compose, lowercase, skip blank lines and '#' comments, and remove duplicates
+    fn default() -> Normalization {
+        Normalization {
+            trim: true,
+            compose: true,
+            lowercase: true,
+            skip_blank: true,
+            comment_prefix: Some('#'),
+            dedupe: true,
+            alphabet: None,
+        }
+    }
+
+}
+
+impl Normalization {
+
+    /// Take every line verbatim
+    pub fn verbatim() -> Normalization {
+        Normalization {
+            trim: false,
+            compose: false,
+            lowercase: false,
+            skip_blank: false,
+            comment_prefix: None,
+            dedupe: false,
+            alphabet: None,
+        }
+    }
+
+    /// Restrict words to the letters of an alphabet
+    pub fn with_alphabet(self, alphabet: Alphabet) -> Normalization {
+        Normalization { alphabet: Some(alphabet), ..self }
+    }
+
+    /// Normalize the lines of a word list.
+    /// Returns each kept word with its 1-based line number, and a report of what was done.
+    pub fn apply(&self, contents: &str) -> (Vec<(usize, String)>, NormalizationReport) {
+        let mut words: Vec<(usize, String)> = Vec::new();
+        let mut report: NormalizationReport = NormalizationReport::default();
+        let mut first_lines: HashMap<String, usize> = HashMap::new();
+        for (index, original) in contents.lines().enumerate() {
+            let line: usize = index + 1;
+            let mut word: &str = original;
+            if let Some(prefix) = self.comment_prefix {
+                word = word.split(prefix).next().unwrap_or_default();
+            }
+            if self.trim {
+                word = word.trim();
+            }
+            if self.skip_blank && word.is_empty() {
+                report.skipped += 1;
+                continue;
+            }
+            let word: String = if self.compose { word.nfc().collect() } else { word.to_string() };
+            let word: String = if self.lowercase { word.to_lowercase() } else { word };
+            let invalid: Option<char> = self.alphabet.as_ref().and_then(|alphabet| alphabet.invalid_letter(&word));
+            if let Some(character) = invalid {
+                report.rejected.push(RejectedWord { line, word, reason: RejectReason::InvalidCharacter(character) });
+                continue;
+            }
+            if self.dedupe {
+                if let Some(&first_line) = first_lines.get(&word) {
+                    report.rejected.push(RejectedWord { line, word, reason: RejectReason::Duplicate { first_line } });
+                    continue;
+                }
+                first_lines.insert(word.clone(), line);
+            }
+            if word != original {
+                report.changed.push(ChangedWord { line, original: original.to_string(), word: word.clone() });
+            }
+            words.push((line, word));
+        }
+        (words, report)
+    }
+
+}
+
+/// A word that was kept but modified by normalization
+#[derive(Debug, PartialEq)]
+pub struct ChangedWord {
+    pub line: usize,
+    pub original: String,
+    pub word: String,
+}
+
+/// A word that was removed by normalization
+#[derive(Debug, PartialEq)]
+pub struct RejectedWord {
+    pub line: usize,
+    pub word: String,
+    pub reason: RejectReason,
+}
+
+/// Why a word was removed by normalization
+#[derive(Debug, PartialEq)]
+pub enum RejectReason {
+
+    /// The word already appeared on an earlier line
+    Duplicate { first_line: usize },
+
+    /// The word contains a character outside the alphabet
+    InvalidCharacter(char),
+}
+
+/// Summary of what normalization did to a word list
+#[derive(Debug, Default)]
+pub struct NormalizationReport {
+
+    /// Words that were kept but modified
+    pub changed: Vec<ChangedWord>,
+
+    /// Words that were removed
+    pub rejected: Vec<RejectedWord>,
+
+    /// Number of blank or comment-only lines that were skipped
+    pub skipped: usize,
+}
+
+impl NormalizationReport {
+
+    /// Whether the word list was already clean
+    pub fn is_clean(&self) -> bool {
+        self.changed.is_empty() && self.rejected.is_empty() && self.skipped == 0
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    // Local crate imports
+    use super::*;
+
+    #[test]
+    fn test_default_normalization() {
+        let contents: &str = "# Five letter words\r\nCrane\r\nslate  \r\n\r\ncrane\r\ntrace # common opener\r\n";
+        let (words, report) = Normalization::default().apply(contents);
+        assert_eq!(words, vec![(2, "crane".to_string()), (3, "slate".to_string()), (6, "trace".to_string())]);
+        assert_eq!(report.skipped, 2);
+        assert_eq!(report.changed.len(), 3);
+        assert_eq!(report.changed[0], ChangedWord { line: 2, original: "Crane".to_string(), word: "crane".to_string() });
+        assert_eq!(report.rejected, vec![
+            RejectedWord { line: 5, word: "crane".to_string(), reason: RejectReason::Duplicate { first_line: 2 } },
+        ]);
+    }
+
+    #[test]
+    fn test_alphabet_restriction() {
+        let (words, report) = Normalization::default().with_alphabet(Alphabet::english()).apply("crane\nit's\nslate\n");
+        assert_eq!(words.len(), 2);
+        assert_eq!(report.rejected[0].reason, RejectReason::InvalidCharacter('\''));
+    }
+
+    #[test]
+    fn test_composes_accented_letters() {
+        let contents: &str = "SEN\u{303}AL\nseñal\nкраи\u{306}\n";
+        let (words, report) = Normalization::default().apply(contents);
+        assert_eq!(words, vec![(1, "señal".to_string()), (3, "край".to_string())]);
+        assert_eq!(report.rejected[0].reason, RejectReason::Duplicate { first_line: 1 });
+        let (words, _) = Normalization::default().with_alphabet(Alphabet::spanish()).apply(contents);
+        assert_eq!(words, vec![(1, "señal".to_string())]);
+    }
+
+    #[test]
+    fn test_verbatim_keeps_every_line() {
+        let (words, report) = Normalization::verbatim().apply("Crane \n\ncrane\n");
+        assert_eq!(words.len(), 3);
+        assert!(report.is_clean());
+    }
+
+}
@@ -0,0 +1,247 @@
+//! Exact optimal search.
+//!
+//! Finds the decision tree that solves every answer in the fewest total guesses by trying every guess
+//! at every node. Results are memoized on the set of remaining candidates, and guesses whose lower bound
+//! cannot beat the best tree found so far are pruned. The result is exact, so it can be used to
+//! benchmark the heuristic strategies.
+
+// Standard library imports
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+// Local crate imports
+use crate::matrix::PatternMatrix;
+use crate::pattern::Pattern;
+use crate::tree::DecisionTree;
+use crate::Library;
+
+/// Exhaustive search for the decision tree with the fewest total guesses
+pub struct OptimalSearch<'a> {
+    library: &'a Library,
+    matrix: &'a PatternMatrix,
+    max_depth: usize,
+    guess_answers: Vec<Option<usize>>,
+    memo: HashMap<(Vec<usize>, usize), Option<Solution>>,
+}
+
+/// Best first guess for a set of candidates and the total guesses it leads to
+#[derive(Clone, Copy)]
+struct Solution {
+    guess: usize,
+    cost: usize,
+}
+
+impl<'a> OptimalSearch<'a> {
+
+    /// Create a search over a library, using a pattern matrix computed from that library.
+    /// Trees are limited to six guesses, as in the standard game.
+    pub fn new(library: &'a Library, matrix: &'a PatternMatrix) -> OptimalSearch<'a> {
+        if matrix.guess_count() != library.guesses.len() || matrix.answer_count() != library.answers.len() {
+            panic!("Pattern matrix was not computed from this library");
+        }
+        let guess_answers: Vec<Option<usize>> = library.guesses.iter().map(|g| library.answer_index(g)).collect();
+        OptimalSearch { library, matrix, max_depth: 6, guess_answers, memo: HashMap::new() }
+    }
+
+    /// Limit trees to the given number of guesses for any answer
+    pub fn with_max_depth(self, max_depth: usize) -> OptimalSearch<'a> {
+        OptimalSearch { max_depth, memo: HashMap::new(), ..self }
+    }
+
+    /// Find the optimal tree for every answer in the library.
+    /// Returns None if no tree solves every answer within the depth limit.
+    pub fn solve(&mut self) -> Option<DecisionTree> {
+        let candidates: Vec<usize> = (0..self.library.answers.len()).collect();
+        self.solve_indices(candidates)
+    }
+
+    /// Find the optimal tree for a set of remaining answers.
+    /// Returns None if no tree solves every answer within the depth limit.
+    pub fn solve_candidates(&mut self, candidates: &[&str]) -> Option<DecisionTree> {
+        let mut indices: Vec<usize> = self.matrix.answer_indices(candidates)
+            .unwrap_or_else(|| panic!("Candidates must all be answers in the library"));
+        indices.sort_unstable();
+        indices.dedup();
+        self.solve_indices(indices)
+    }
+
+    /// Search for a sorted set of answer indices and build the resulting tree
+    fn solve_indices(&mut self, candidates: Vec<usize>) -> Option<DecisionTree> {
+        if candidates.is_empty() {
+            return None;
+        }
+        self.search(&candidates, self.max_depth)?;
+        Some(self.build(&candidates, self.max_depth))
+    }
+
+    /// Fewest total guesses needed to solve a set of candidates within a number of guesses
+    fn search(&mut self, candidates: &[usize], depth: usize) -> Option<usize> {
+        if depth == 0 {
+            return None;
+        }
+        if candidates.len() == 1 {
+            return Some(1);
+        }
+        if depth == 1 {
+            return None;
+        }
+        let key: (Vec<usize>, usize) = (candidates.to_vec(), depth);
+        if let Some(solution) = self.memo.get(&key) {
+            return solution.map(|solution| solution.cost);
+        }
+
+        // Try the guesses with the lowest lower bounds first so that good trees are found early
+        let mut bounds: Vec<(usize, usize)> = (0..self.library.guesses.len())
+            .filter_map(|guess| {
+                let buckets: Vec<Vec<usize>> = self.partition(guess, candidates);
+                if buckets.len() == 1 && buckets[0].len() == candidates.len() {
+                    return None;
+                }
+                Some((candidates.len() + buckets.iter().map(|b| lower_bound(b.len())).sum::<usize>(), guess))
+            })
+            .collect();
+        bounds.sort_unstable();
+
+        let mut best: Option<Solution> = None;
+        for (bound, guess) in bounds {
+            let best_cost: usize = best.map_or(usize::MAX, |solution| solution.cost);
+            if bound >= best_cost {
+                break;
+            }
+            let mut buckets: Vec<Vec<usize>> = self.partition(guess, candidates);
+            buckets.sort_unstable_by_key(|bucket| std::cmp::Reverse(bucket.len()));
+            let mut cost: Option<usize> = Some(candidates.len());
+            let mut remaining: usize = bound - candidates.len();
+            for bucket in &buckets {
+                remaining -= lower_bound(bucket.len());
+                cost = cost.zip(self.search(bucket, depth - 1)).map(|(total, sub)| total + sub);
+                if cost.is_none_or(|total| total + remaining >= best_cost) {
+                    cost = None;
+                    break;
+                }
+            }
+            if let Some(cost) = cost {
+                best = Some(Solution { guess, cost });
+            }
+        }
+        self.memo.insert(key, best);
+        best.map(|solution| solution.cost)
+    }
+
+    /// Build the tree for a set of candidates that has already been searched
+    fn build(&self, candidates: &[usize], depth: usize) -> DecisionTree {
+        if candidates.len() == 1 {
+            return DecisionTree::leaf(&self.library.answers[candidates[0]]);
+        }
+        let solution: Solution = self.memo[&(candidates.to_vec(), depth)]
+            .expect("Searched candidates must have a solution");
+        let branches: BTreeMap<Pattern, DecisionTree> = self.partition(solution.guess, candidates).iter()
+            .map(|bucket| (self.matrix.get(solution.guess, bucket[0]), self.build(bucket, depth - 1)))
+            .collect();
+        DecisionTree {
+            guess: self.library.guesses[solution.guess].clone(),
+            is_answer: self.guess_answers[solution.guess].is_some_and(|answer| candidates.contains(&answer)),
+            branches,
+        }
+    }
+
+    /// Split candidates by the pattern a guess produces, leaving out the candidate the guess solves
+    fn partition(&self, guess: usize, candidates: &[usize]) -> Vec<Vec<usize>> {
+        let mut buckets: BTreeMap<Pattern, Vec<usize>> = BTreeMap::new();
+        for &answer in candidates {
+            if self.guess_answers[guess] != Some(answer) {
+                buckets.entry(self.matrix.get(guess, answer)).or_default().push(answer);
+            }
+        }
+        buckets.into_values().collect()
+    }
+
+}
+
+/// Fewest total guesses any tree could need for a set of candidates.
+/// One guess can solve at most one of them, so every other candidate needs at least two.
+fn lower_bound(candidate_count: usize) -> usize {
+    (2 * candidate_count).saturating_sub(1)
+}
+
+#[cfg(test)]
+mod tests {
+
+    // Local crate imports
+    use super::*;
+    use crate::simulate::Simulation;
+    use crate::solver::EntropyStrategy;
+    use crate::solver::MinimaxStrategy;
+    use crate::tests::create_word_library;
+    use crate::tests::REPEATED_LETTER_WORDS;
+
+    /// Number of guesses the tree needs to solve an answer
+    fn guesses_to_solve(tree: &DecisionTree, answer: &str) -> usize {
+        let mut node: &DecisionTree = tree;
+        let mut turns: usize = 1;
+        while node.guess != answer {
+            node = node.branch(Pattern::evaluate(&node.guess, answer)).expect("Tree must cover every answer");
+            turns += 1;
+        }
+        turns
+    }
+
+    /// Fewest total guesses for a set of candidates, by trying every tree without pruning or memoization
+    fn brute_force(library: &Library, candidates: &[&str], depth: usize) -> Option<usize> {
+        match (candidates.len(), depth) {
+            (_, 0) => return None,
+            (1, _) => return Some(1),
+            (_, 1) => return None,
+            _ => {},
+        }
+        library.guesses.iter().filter_map(|guess| {
+            let mut buckets: BTreeMap<Pattern, Vec<&str>> = BTreeMap::new();
+            for &answer in candidates.iter().filter(|&&answer| answer != guess) {
+                buckets.entry(Pattern::evaluate(guess, answer)).or_default().push(answer);
+            }
+            if buckets.len() == 1 && buckets.values().next().unwrap().len() == candidates.len() {
+                return None;
+            }
+            buckets.values().try_fold(candidates.len(), |total, bucket| {
+                Some(total + brute_force(library, bucket, depth - 1)?)
+            })
+        }).min()
+    }
+
+    #[test]
+    fn test_optimal_tree_solves_every_answer() {
+        let library: Library = create_word_library(&REPEATED_LETTER_WORDS, &["zzzzz"]);
+        let matrix: PatternMatrix = PatternMatrix::compute(&library);
+        let tree: DecisionTree = OptimalSearch::new(&library, &matrix).solve().unwrap();
+        assert_eq!(tree.answer_count(), library.answers.len());
+        let total: usize = library.answers.iter().map(|answer| guesses_to_solve(&tree, answer)).sum();
+        assert_eq!(total, tree.total_guesses());
+    }
+
+    #[test]
+    fn test_optimal_matches_brute_force() {
+        let library: Library = create_word_library(&REPEATED_LETTER_WORDS, &["zzzzz"]);
+        let matrix: PatternMatrix = PatternMatrix::compute(&library);
+        let answers: Vec<&str> = library.answers.iter().map(|a| a.as_str()).collect();
+        for depth in 1..=4 {
+            let tree: Option<DecisionTree> = OptimalSearch::new(&library, &matrix).with_max_depth(depth).solve();
+            assert_eq!(tree.as_ref().map(|tree| tree.total_guesses()), brute_force(&library, &answers, depth));
+            assert!(tree.is_none_or(|tree| tree.depth() <= depth));
+        }
+        let subset: [&str; 4] = ["trace", "react", "cater", "crane"];
+        let tree: DecisionTree = OptimalSearch::new(&library, &matrix).solve_candidates(&subset).unwrap();
+        assert_eq!(Some(tree.total_guesses()), brute_force(&library, &subset, 6));
+    }
+
+    #[test]
+    fn test_optimal_beats_heuristics() {
+        let library: Library = create_word_library(&REPEATED_LETTER_WORDS, &["zzzzz"]);
+        let matrix: PatternMatrix = PatternMatrix::compute(&library);
+        let tree: DecisionTree = OptimalSearch::new(&library, &matrix).solve().unwrap();
+        let entropy: f64 = Simulation::new(6).run(&library, &EntropyStrategy::new()).mean();
+        let minimax: f64 = Simulation::new(6).run(&library, &MinimaxStrategy::new()).mean();
+        assert!(tree.average_guesses() <= entropy);
+        assert!(tree.average_guesses() <= minimax);
+    }
+
+}
@@ -0,0 +1,266 @@
+//! Compact feedback patterns.
+//!
+//! Packs the letter states of a guess result into a single base-3 integer so that results can be
+//! compared, hashed and bucketed without allocating.
+
+// Local crate imports
+use crate::rules::FeedbackRules;
+use crate::rules::NytRules;
+use crate::GuessResult;
+use crate::LetterState;
+
+/// Longest word whose feedback fits in a pattern
+pub const MAX_PATTERN_LENGTH: usize = 20;
+
+/// Longest word whose patterns are counted in a table with one slot per pattern (3^8 = 6561 slots)
+pub(crate) const MAX_TABLE_LENGTH: usize = 8;
+
+/// Feedback for a whole guess packed into a base-3 integer.
+/// The first letter is the most significant digit, with Absent = 0, Present = 1 and Correct = 2,
+/// so patterns order the same way as their letter states.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pattern(u32);
+
+impl Pattern {
+
+    /// Pack a sequence of letter states into a pattern
+    pub fn from_states(states: &[LetterState]) -> Pattern {
+        if states.len() > MAX_PATTERN_LENGTH {
+            panic!("Patterns support at most {} letters, got {}", MAX_PATTERN_LENGTH, states.len());
+        }
+        Pattern(states.iter().fold(0, |code, state| code * 3 + state_digit(state)))
+    }
+
+    /// Unpack a pattern into the letter states of a word of the given length
+    pub fn to_states(self, word_length: usize) -> Vec<LetterState> {
+        let mut code: u32 = self.0;
+        let mut states: Vec<LetterState> = (0..word_length).map(|_| {
+            let state: LetterState = digit_state(code % 3);
+            code /= 3;
+            state
+        }).collect();
+        states.reverse();
+        states
+    }
+
+    /// Create a pattern from its integer code
+    pub fn from_code(code: u32) -> Pattern {
+        Pattern(code)
+    }
+
+    /// Integer code of the pattern
+    pub fn code(self) -> u32 {
+        self.0
+    }
+
+    /// Position of the pattern in a table with one slot per possible pattern
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    /// Number of distinct patterns for words of the given length
+    pub fn count(word_length: usize) -> usize {
+        3usize.pow(word_length as u32)
+    }
+
+    /// Pattern produced when every letter is correct
+    pub fn all_correct(word_length: usize) -> Pattern {
+        Pattern((Pattern::count(word_length) - 1) as u32)
+    }
+
+    /// Evaluate a guess against an answer directly into a pattern using the New York Times rules
+    pub fn evaluate(guess: &str, answer: &str) -> Pattern {
+        Pattern::evaluate_with(&NytRules, guess, answer)
+    }
+
+    /// Evaluate a guess against an answer directly into a pattern, without building a GuessResult
+    pub fn evaluate_with(rules: &dyn FeedbackRules, guess: &str, answer: &str) -> Pattern {
+        let mut guess_chars: [char; MAX_PATTERN_LENGTH] = ['\0'; MAX_PATTERN_LENGTH];
+        let mut answer_chars: [char; MAX_PATTERN_LENGTH] = ['\0'; MAX_PATTERN_LENGTH];
+        let mut states: [LetterState; MAX_PATTERN_LENGTH] = [const { LetterState::Absent }; MAX_PATTERN_LENGTH];
+        let length: usize = fill_chars(guess, &mut guess_chars);
+        if fill_chars(answer, &mut answer_chars) != length {
+            panic!("Guess and answer must be the same length");
+        }
+        rules.evaluate(&guess_chars[..length], &answer_chars[..length], &mut states[..length]);
+        Pattern::from_states(&states[..length])
+    }
+
+}
+
+impl GuessResult {
+
+    /// Packed pattern of this result
+    pub fn pattern(&self) -> Pattern {
+        Pattern::from_states(&self.states)
+    }
+
+    /// Build a result for a guess from a packed pattern
+    pub fn from_pattern(guess: &str, pattern: Pattern) -> GuessResult {
+        let states: Vec<LetterState> = pattern.to_states(guess.chars().count());
+        GuessResult { guess: guess.to_string(), states }
+    }
+
+}
+
+/// Sizes of the non-empty pattern buckets a guess splits the candidates into, in pattern order
+pub fn bucket_sizes(guess: &str, candidates: &[&str]) -> Vec<usize> {
+    bucket_sizes_with(&NytRules, guess, candidates)
+}
+
+/// Sizes of the non-empty pattern buckets a guess splits the candidates into under the given rules
+pub fn bucket_sizes_with(rules: &dyn FeedbackRules, guess: &str, candidates: &[&str]) -> Vec<usize> {
+    sizes_of(guess.chars().count(), candidates.iter().map(|answer| Pattern::evaluate_with(rules, guess, answer)))
+}
+
+/// Sizes of the non-empty buckets of patterns for words of the given length, in pattern order.
+/// Short words are counted in a table indexed by pattern; longer words, whose tables would be too large,
+/// are sorted and counted in runs instead.
+pub(crate) fn sizes_of(word_length: usize, patterns: impl Iterator<Item = Pattern>) -> Vec<usize> {
+    if word_length <= MAX_TABLE_LENGTH {
+        return count_table(word_length, patterns).into_iter().filter(|&count| count > 0).collect();
+    }
+    let mut patterns: Vec<Pattern> = patterns.collect();
+    patterns.sort_unstable();
+    patterns.chunk_by(|a, b| a == b).map(|run| run.len()).collect()
+}
+
+/// Count patterns for words of the given length in a table with one slot per possible pattern
+pub(crate) fn count_table(word_length: usize, patterns: impl Iterator<Item = Pattern>) -> Vec<usize> {
+    if word_length > MAX_TABLE_LENGTH {
+        panic!("Pattern tables support at most {} letters, got {}", MAX_TABLE_LENGTH, word_length);
+    }
+    let mut counts: Vec<usize> = vec![0; Pattern::count(word_length)];
+    for pattern in patterns {
+        counts[pattern.index()] += 1;
+    }
+    counts
+}
+
+/// Copy the characters of a word into a fixed buffer, returning the number of characters
+fn fill_chars(word: &str, buffer: &mut [char; MAX_PATTERN_LENGTH]) -> usize {
+    let mut length: usize = 0;
+    for c in word.chars() {
+        if length == MAX_PATTERN_LENGTH {
+            panic!("Patterns support at most {} letters: {}", MAX_PATTERN_LENGTH, word);
+        }
+        buffer[length] = c;
+        length += 1;
+    }
+    length
+}
+
+/// Base-3 digit for a letter state
+fn state_digit(state: &LetterState) -> u32 {
+    match state {
+        LetterState::Absent => 0,
+        LetterState::Present => 1,
+        LetterState::Correct => 2,
+    }
+}
+
+/// Letter state for a base-3 digit
+fn digit_state(digit: u32) -> LetterState {
+    match digit {
+        0 => LetterState::Absent,
+        1 => LetterState::Present,
+        _ => LetterState::Correct,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    // Standard library imports
+    use std::collections::BTreeMap;
+
+    // Local crate imports
+    use super::*;
+
+    #[test]
+    fn test_round_trip_every_pattern() {
+        for code in 0..Pattern::count(5) as u32 {
+            let pattern: Pattern = Pattern::from_code(code);
+            assert_eq!(Pattern::from_states(&pattern.to_states(5)), pattern);
+        }
+    }
+
+    #[test]
+    fn test_evaluate_matches_guess_result() {
+        let words: [&str; 6] = ["crane", "trace", "eerie", "ether", "speed", "abide"];
+        for guess in words {
+            for answer in words {
+                let result: GuessResult = GuessResult::evaluate_guess(guess, answer);
+                assert_eq!(Pattern::evaluate(guess, answer), result.pattern());
+                assert!(GuessResult::from_pattern(guess, result.pattern()).states == result.states);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ordering_follows_letter_states() {
+        let all_absent: Pattern = Pattern::from_states(&[LetterState::Absent, LetterState::Absent]);
+        let first_present: Pattern = Pattern::from_states(&[LetterState::Present, LetterState::Absent]);
+        let last_correct: Pattern = Pattern::from_states(&[LetterState::Absent, LetterState::Correct]);
+        assert_eq!(all_absent.code(), 0);
+        assert!(all_absent < last_correct);
+        assert!(last_correct < first_present);
+        assert_eq!(Pattern::evaluate("crane", "crane"), Pattern::all_correct(5));
+    }
+
+    #[test]
+    fn test_bucket_counts_cover_all_candidates() {
+        let candidates: [&str; 4] = ["crane", "slate", "plumb", "brick"];
+        let counts: Vec<usize> = count_table(5, candidates.iter().map(|answer| Pattern::evaluate("trace", answer)));
+        assert_eq!(counts.len(), 243);
+        assert_eq!(counts.iter().sum::<usize>(), candidates.len());
+        let sizes: Vec<usize> = bucket_sizes("trace", &candidates);
+        assert_eq!(sizes, counts.into_iter().filter(|&count| count > 0).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn test_bucket_sizes_agree_across_word_lengths() {
+        for word_length in [MAX_TABLE_LENGTH, MAX_TABLE_LENGTH + 1] {
+            let library: crate::Library = crate::tests::create_generated_library(word_length, 40);
+            let candidates: Vec<&str> = library.answers.iter().map(|w| w.as_str()).collect();
+            let mut patterns: Vec<Pattern> = candidates.iter()
+                .map(|answer| Pattern::evaluate(candidates[0], answer))
+                .collect();
+            patterns.sort_unstable();
+            let expected: Vec<usize> = patterns.chunk_by(|a, b| a == b).map(|run| run.len()).collect();
+            assert_eq!(bucket_sizes(candidates[0], &candidates), expected, "Wrong sizes for length {}", word_length);
+        }
+    }
+
+    #[test]
+    fn test_bucket_sizes_count_long_words() {
+        for word_length in [9, 11] {
+            let library: crate::Library = crate::tests::create_generated_library(word_length, 40);
+            let candidates: Vec<&str> = library.answers.iter().map(|w| w.as_str()).collect();
+            let mut counts: BTreeMap<Pattern, usize> = BTreeMap::new();
+            for answer in &candidates {
+                *counts.entry(Pattern::evaluate(candidates[0], answer)).or_default() += 1;
+            }
+            let expected: Vec<usize> = counts.into_values().collect();
+            assert_eq!(bucket_sizes(candidates[0], &candidates), expected, "Wrong sizes for length {}", word_length);
+            assert_eq!(expected.iter().sum::<usize>(), candidates.len());
+        }
+    }
+
+    #[test]
+    fn test_patterns_fit_long_words() {
+        for word_length in 3..=MAX_PATTERN_LENGTH {
+            let all_correct: Pattern = Pattern::all_correct(word_length);
+            assert_eq!(all_correct.to_states(word_length).len(), word_length);
+            assert!(all_correct.to_states(word_length).iter().all(|state| *state == LetterState::Correct));
+            assert_eq!(Pattern::from_states(&all_correct.to_states(word_length)), all_correct);
+        }
+        let guess: &str = "abcdefghijk";
+        let answer: &str = "kbcdefghija";
+        let result: GuessResult = GuessResult::evaluate_guess(guess, answer);
+        assert_eq!(Pattern::evaluate(guess, answer), result.pattern());
+        assert!(GuessResult::from_pattern(guess, result.pattern()).states == result.states);
+    }
+
+}
@@ -0,0 +1,237 @@
+//! Rendering.
+//!
+//! Turns guess results into text for wherever the output ends up. Emoji renderers show only the
+//! colors, like the game's share text, in the classic light and dark themes or the high contrast
+//! theme for colorblind players. ANSI renderers color the letters for terminals, ASCII renderers
+//! write feedback codes for logs, and HTML renderers write tiles for web pages.
+//!
+//! The Display implementations of LetterState and GuessResult use the classic dark theme.
+
+// Standard library imports
+use std::fmt;
+
+// Local crate imports
+use crate::GuessResult;
+use crate::LetterState;
+
+/// A way of writing guess results as text
+pub trait Renderer: Send + Sync {
+
+    /// Short name identifying the renderer
+    fn name(&self) -> &str;
+
+    /// Render a letter state on its own, e.g. in a share grid where the letters are not shown
+    fn render_state(&self, state: &LetterState) -> String;
+
+    /// Render one letter of a guess. By default only the state is shown.
+    fn render_letter(&self, _letter: char, state: &LetterState) -> String {
+        self.render_state(state)
+    }
+
+    /// Render every letter of a guess result
+    fn render(&self, result: &GuessResult) -> String {
+        result.guess.chars().zip(result.states())
+            .map(|(letter, state)| self.render_letter(letter, state))
+            .collect()
+    }
+
+}
+
+/// Emoji squares, as in the game's share text
+#[derive(Clone, Copy, Debug)]
+pub struct EmojiRenderer {
+    name: &'static str,
+    correct: char,
+    present: char,
+    absent: char,
+}
+
+impl EmojiRenderer {
+
+    /// Green and yellow on black, as in the game's dark mode
+    pub fn dark() -> EmojiRenderer {
+        EmojiRenderer { name: "dark", correct: '🟩', present: '🟨', absent: '⬛' }
+    }
+
+    /// Green and yellow on white, as in the game's light mode
+    pub fn light() -> EmojiRenderer {
+        EmojiRenderer { name: "light", correct: '🟩', present: '🟨', absent: '⬜' }
+    }
+
+    /// Orange and blue on black, as in the game's high contrast mode for colorblind players
+    pub fn high_contrast() -> EmojiRenderer {
+        EmojiRenderer { name: "contrast", correct: '🟧', present: '🟦', absent: '⬛' }
+    }
+
+}
+
+impl Default for EmojiRenderer {
+
+    fn default() -> EmojiRenderer {
+        EmojiRenderer::dark()
+    }
+
+}
+
+impl Renderer for EmojiRenderer {
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn render_state(&self, state: &LetterState) -> String {
+        match state {
+            LetterState::Correct => self.correct,
+            LetterState::Present => self.present,
+            LetterState::Absent => self.absent,
+        }.to_string()
+    }
+
+}
+
+/// Uppercase letters on colored tiles, using ANSI escape codes
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AnsiRenderer;
+
+impl Renderer for AnsiRenderer {
+
+    fn name(&self) -> &str {
+        "ansi"
+    }
+
+    fn render_state(&self, state: &LetterState) -> String {
+        self.render_letter(' ', state)
+    }
+
+    fn render_letter(&self, letter: char, state: &LetterState) -> String {
+        // Bold white text on a green, yellow or gray background
+        let background: u8 = match state {
+            LetterState::Correct => 42,
+            LetterState::Present => 43,
+            LetterState::Absent => 100,
+        };
+        format!("\x1b[1;97;{}m {} \x1b[0m", background, letter.to_uppercase())
+    }
+
+}
+
+/// Feedback codes, G for correct, Y for present and . for absent, which the feedback parser reads back
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AsciiRenderer;
+
+impl Renderer for AsciiRenderer {
+
+    fn name(&self) -> &str {
+        "ascii"
+    }
+
+    fn render_state(&self, state: &LetterState) -> String {
+        match state {
+            LetterState::Correct => "G",
+            LetterState::Present => "Y",
+            LetterState::Absent => ".",
+        }.to_string()
+    }
+
+}
+
+/// Span elements for each letter, with the classes wordle-tile and wordle-correct, wordle-present or
+/// wordle-absent for styling. Each result is wrapped in a div with the class wordle-row.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HtmlRenderer;
+
+impl Renderer for HtmlRenderer {
+
+    fn name(&self) -> &str {
+        "html"
+    }
+
+    fn render_state(&self, state: &LetterState) -> String {
+        format!("<span class=\"wordle-tile wordle-{}\"></span>", state_class(state))
+    }
+
+    fn render_letter(&self, letter: char, state: &LetterState) -> String {
+        let letter: String = match letter {
+            '&' => "&amp;".to_string(),
+            '<' => "&lt;".to_string(),
+            '>' => "&gt;".to_string(),
+            '"' => "&quot;".to_string(),
+            letter => letter.to_string(),
+        };
+        format!("<span class=\"wordle-tile wordle-{}\">{}</span>", state_class(state), letter)
+    }
+
+    fn render(&self, result: &GuessResult) -> String {
+        let tiles: String = result.guess.chars().zip(result.states())
+            .map(|(letter, state)| self.render_letter(letter, state))
+            .collect();
+        format!("<div class=\"wordle-row\">{}</div>", tiles)
+    }
+
+}
+
+/// Class name suffix for a letter state
+fn state_class(state: &LetterState) -> &'static str {
+    match state {
+        LetterState::Correct => "correct",
+        LetterState::Present => "present",
+        LetterState::Absent => "absent",
+    }
+}
+
+impl fmt::Display for LetterState {
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", EmojiRenderer::dark().render_state(self))
+    }
+
+}
+
+impl fmt::Display for GuessResult {
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", EmojiRenderer::dark().render(self))
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    // Local crate imports
+    use super::*;
+
+    #[test]
+    fn test_emoji_themes() {
+        let result: GuessResult = GuessResult::evaluate_guess("crane", "caper");
+        assert_eq!(result.to_string(), "🟩🟨🟨⬛🟨");
+        assert_eq!(LetterState::Absent.to_string(), "⬛");
+        assert_eq!(EmojiRenderer::light().render(&result), "🟩🟨🟨⬜🟨");
+        assert_eq!(EmojiRenderer::high_contrast().render(&result), "🟧🟦🟦⬛🟦");
+    }
+
+    #[test]
+    fn test_text_renderers() {
+        let result: GuessResult = GuessResult::evaluate_guess("crane", "caper");
+        assert_eq!(AsciiRenderer.render(&result), "GYY.Y");
+        assert_eq!(AnsiRenderer.render_letter('ñ', &LetterState::Present), "\x1b[1;97;43m Ñ \x1b[0m");
+        assert_eq!(AnsiRenderer.render(&result).matches("\x1b[0m").count(), 5);
+        let html: String = HtmlRenderer.render(&GuessResult::evaluate_guess("ab", "ba"));
+        assert_eq!(html, concat!(
+            "<div class=\"wordle-row\"><span class=\"wordle-tile wordle-present\">a</span>",
+            "<span class=\"wordle-tile wordle-present\">b</span></div>",
+        ));
+    }
+
+    #[test]
+    fn test_rendered_feedback_parses_back() {
+        let result: GuessResult = GuessResult::evaluate_guess("eerie", "ether");
+        let renderers: [&dyn Renderer; 4] =
+            [&EmojiRenderer::dark(), &EmojiRenderer::light(), &EmojiRenderer::high_contrast(), &AsciiRenderer];
+        for renderer in renderers {
+            let parsed: GuessResult = GuessResult::from_feedback("eerie", &renderer.render(&result)).unwrap();
+            assert_eq!(parsed.pattern(), result.pattern(), "Wrong states read back from {}", renderer.name());
+        }
+    }
+
+}
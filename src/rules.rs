@@ -0,0 +1,116 @@
+//! Feedback rule sets.
+//!
+//! Wordle clones disagree on how repeated letters are scored. A rule set decides the state of every
+//! letter in a guess, and everything that evaluates guesses can be switched between rule sets.
+
+// Local crate imports
+use crate::LetterState;
+
+/// A way of scoring every letter of a guess against an answer
+pub trait FeedbackRules: Send + Sync {
+
+    /// Short name identifying the rule set
+    fn name(&self) -> &str;
+
+    /// Fill in the state of every letter of a guess. The guess, answer and states all have the same length.
+    fn evaluate(&self, guess: &[char], answer: &[char], states: &mut [LetterState]);
+
+}
+
+/// Rules used by the New York Times game.
+/// The first pass marks letters in the correct position. The second pass marks the remaining guess
+/// letters as Present from left to right, for as long as the answer has copies of that letter that
+/// have not already been matched or claimed by an earlier Present.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NytRules;
+
+impl FeedbackRules for NytRules {
+
+    fn name(&self) -> &str {
+        "nyt"
+    }
+
+    fn evaluate(&self, guess: &[char], answer: &[char], states: &mut [LetterState]) {
+        mark_correct(guess, answer, states);
+        for i in 0..guess.len() {
+            if states[i] == LetterState::Correct {
+                continue;
+            }
+            let g: char = guess[i];
+            let unmatched: usize = (0..answer.len())
+                .filter(|&j| answer[j] == g && states[j] != LetterState::Correct)
+                .count();
+            let claimed: usize = (0..i)
+                .filter(|&j| guess[j] == g && states[j] == LetterState::Present)
+                .count();
+            if claimed < unmatched {
+                states[i] = LetterState::Present;
+            }
+        }
+    }
+
+}
+
+/// Rules that mark every copy of a letter as Present whenever the answer contains that letter
+/// at a position that is not already Correct, regardless of how many copies the answer has
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NaiveRules;
+
+impl FeedbackRules for NaiveRules {
+
+    fn name(&self) -> &str {
+        "naive"
+    }
+
+    fn evaluate(&self, guess: &[char], answer: &[char], states: &mut [LetterState]) {
+        mark_correct(guess, answer, states);
+        for i in 0..guess.len() {
+            if states[i] != LetterState::Correct
+                && (0..answer.len()).any(|j| answer[j] == guess[i] && states[j] != LetterState::Correct)
+            {
+                states[i] = LetterState::Present;
+            }
+        }
+    }
+
+}
+
+/// Mark letters in the correct position as Correct and every other letter as Absent
+fn mark_correct(guess: &[char], answer: &[char], states: &mut [LetterState]) {
+    for (i, state) in states.iter_mut().enumerate() {
+        *state = if guess[i] == answer[i] { LetterState::Correct } else { LetterState::Absent };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    // Local crate imports
+    use super::*;
+    use crate::GuessResult;
+
+    #[test]
+    fn test_rules_differ_on_repeated_letters() {
+        let nyt: GuessResult = GuessResult::evaluate_guess_with(&NytRules, "eerie", "ether");
+        let naive: GuessResult = GuessResult::evaluate_guess_with(&NaiveRules, "eerie", "ether");
+        assert!(nyt.states() == [
+            LetterState::Correct, LetterState::Present, LetterState::Present, LetterState::Absent, LetterState::Absent,
+        ]);
+        assert!(naive.states() == [
+            LetterState::Correct, LetterState::Present, LetterState::Present, LetterState::Absent, LetterState::Present,
+        ]);
+    }
+
+    #[test]
+    fn test_rules_agree_without_repeated_letters() {
+        let words: [&str; 4] = ["crane", "slate", "trace", "plumb"];
+        for guess in words {
+            for answer in words {
+                let nyt: GuessResult = GuessResult::evaluate_guess_with(&NytRules, guess, answer);
+                let naive: GuessResult = GuessResult::evaluate_guess_with(&NaiveRules, guess, answer);
+                assert!(nyt.states() == naive.states(), "Rules disagree for {} against {}", guess, answer);
+            }
+        }
+    }
+
+}
@@ -0,0 +1,354 @@
+//! Share grids.
+//!
+//! Reads and writes the text players post after a game:
+//!
+//! ```text
+//! Wordle 1,234 4/6*
+//!
+//! ⬛🟨⬛⬛⬛
+//! ⬛⬛🟩🟨⬛
+//! 🟩🟩🟩⬛🟩
+//! 🟩🟩🟩🟩🟩
+//! ```
+//!
+//! The header gives the puzzle number, the number of guesses (X for a lost game), the turn limit, and
+//! a star if the game was played in hard mode. Rows are read with the feedback parser, so light mode
+//! and high contrast grids are accepted too.
+
+// Standard library imports
+use std::error::Error;
+use std::fmt;
+
+// Local crate imports
+use crate::feedback;
+use crate::feedback::FeedbackError;
+use crate::pattern::Pattern;
+use crate::pattern::MAX_PATTERN_LENGTH;
+use crate::render::EmojiRenderer;
+use crate::render::Renderer;
+use crate::GuessResult;
+use crate::LetterState;
+
+/// Name at the start of the header
+const GAME_NAME: &str = "Wordle";
+
+/// Why share text could not be read. Line numbers start at 1.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ShareError {
+
+    /// There is no header line
+    MissingHeader,
+
+    /// The header is not `Wordle <puzzle> <score>/<turns>`
+    InvalidHeader { text: String },
+
+    /// A row could not be read as feedback
+    InvalidRow { line: usize, source: FeedbackError },
+
+    /// A row has a different number of letters than the first row
+    RowLength { line: usize, expected: usize, found: usize },
+
+    /// A row has more letters than feedback patterns support
+    RowTooLong { line: usize, found: usize, max: usize },
+
+    /// The rows do not match the score in the header
+    ScoreMismatch { score: String, rows: usize },
+
+    /// The grid's words are longer than feedback patterns support
+    UnsupportedWordLength { found: usize, max: usize },
+
+    /// A row is not a pattern for words of the grid's length. Rows are numbered from 1.
+    InvalidPattern { row: usize, code: u32 },
+}
+
+impl fmt::Display for ShareError {
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShareError::MissingHeader => write!(f, "Share text has no header"),
+            ShareError::InvalidHeader { text } => write!(f, "Header {:?} is not a Wordle score", text),
+            ShareError::InvalidRow { line, source } => write!(f, "Line {}: {}", line, source),
+            ShareError::RowLength { line, expected, found } => {
+                write!(f, "Line {}: row has {} letters, expected {}", line, found, expected)
+            },
+            ShareError::RowTooLong { line, found, max } => {
+                write!(f, "Line {}: row has {} letters, but at most {} are supported", line, found, max)
+            },
+            ShareError::ScoreMismatch { score, rows } => {
+                write!(f, "Score {} does not match a grid of {} rows", score, rows)
+            },
+            ShareError::UnsupportedWordLength { found, max } => {
+                write!(f, "Grid has {} letters per row, but at most {} are supported", found, max)
+            },
+            ShareError::InvalidPattern { row, code } => write!(f, "Row {} has invalid pattern code {}", row, code),
+        }
+    }
+
+}
+
+impl Error for ShareError {
+
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ShareError::InvalidRow { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+
+}
+
+/// Result of one game, as shared
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "GridRows"))]
+pub struct ShareGrid {
+
+    /// Puzzle number
+    pub puzzle: u32,
+
+    /// Number of guesses allowed
+    pub max_turns: usize,
+
+    /// Whether the game was played in hard mode
+    pub hard_mode: bool,
+
+    /// Number of letters in each row
+    pub word_length: usize,
+
+    /// Feedback for each guess, in order
+    pub rows: Vec<Pattern>,
+}
+
+/// Serialized form of a share grid, checked when deserialized
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct GridRows {
+    puzzle: u32,
+    max_turns: usize,
+    hard_mode: bool,
+    word_length: usize,
+    rows: Vec<Pattern>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<GridRows> for ShareGrid {
+
+    type Error = ShareError;
+
+    fn try_from(grid: GridRows) -> Result<ShareGrid, ShareError> {
+        if grid.word_length > MAX_PATTERN_LENGTH {
+            return Err(ShareError::UnsupportedWordLength { found: grid.word_length, max: MAX_PATTERN_LENGTH });
+        }
+        let patterns: usize = Pattern::count(grid.word_length);
+        if let Some((index, row)) = grid.rows.iter().enumerate().find(|(_, row)| row.index() >= patterns) {
+            return Err(ShareError::InvalidPattern { row: index + 1, code: row.code() });
+        }
+        let GridRows { puzzle, max_turns, hard_mode, word_length, rows } = grid;
+        Ok(ShareGrid { puzzle, max_turns, hard_mode, word_length, rows })
+    }
+
+}
+
+impl ShareGrid {
+
+    /// Create a grid from the results of a game
+    pub fn from_results(puzzle: u32, results: &[GuessResult], max_turns: usize, hard_mode: bool) -> ShareGrid {
+        let word_length: usize = match results.first() {
+            Some(result) => result.states().len(),
+            None => panic!("A share grid needs at least one guess"),
+        };
+        ShareGrid { puzzle, max_turns, hard_mode, word_length, rows: results.iter().map(|r| r.pattern()).collect() }
+    }
+
+    /// Whether the last row is entirely correct
+    pub fn is_solved(&self) -> bool {
+        self.rows.last() == Some(&Pattern::all_correct(self.word_length))
+    }
+
+    /// Number of guesses needed, or None if the game was lost
+    pub fn turns(&self) -> Option<usize> {
+        self.is_solved().then_some(self.rows.len())
+    }
+
+    /// Score as shown in the header, e.g. "4/6*" or "X/6"
+    pub fn score(&self) -> String {
+        let turns: String = self.turns().map_or("X".to_string(), |turns| turns.to_string());
+        format!("{}/{}{}", turns, self.max_turns, if self.hard_mode { "*" } else { "" })
+    }
+
+    /// Letter states of each row
+    pub fn states(&self) -> Vec<Vec<LetterState>> {
+        self.rows.iter().map(|row| row.to_states(self.word_length)).collect()
+    }
+
+    /// Render the grid as share text, with dark mode squares
+    pub fn to_text(&self) -> String {
+        self.to_text_with(&EmojiRenderer::dark())
+    }
+
+    /// Render the grid as share text, drawing each square with a renderer
+    pub fn to_text_with(&self, renderer: &dyn Renderer) -> String {
+        let mut text: String = format!("{} {} {}\n", GAME_NAME, format_puzzle(self.puzzle), self.score());
+        for states in self.states() {
+            text.push('\n');
+            text.extend(states.iter().map(|state| renderer.render_state(state)));
+        }
+        text.push('\n');
+        text
+    }
+
+    /// Read share text. Blank lines are skipped, and anything after the grid, such as a link, is ignored.
+    pub fn parse(text: &str) -> Result<ShareGrid, ShareError> {
+        let mut lines = text.lines().enumerate().map(|(index, line)| (index + 1, line.trim()));
+        let header: &str = lines.by_ref().map(|(_, line)| line).find(|line| !line.is_empty())
+            .ok_or(ShareError::MissingHeader)?;
+        let invalid_header = || ShareError::InvalidHeader { text: header.to_string() };
+        let (puzzle, solved_in, max_turns, hard_mode): (u32, Option<usize>, usize, bool) =
+            parse_header(header).ok_or_else(invalid_header)?;
+
+        let mut rows: Vec<Pattern> = Vec::new();
+        let mut word_length: usize = 0;
+        for (line, row) in lines {
+            if row.is_empty() {
+                if rows.is_empty() {
+                    continue;
+                }
+                break;
+            }
+            let states: Vec<LetterState> = match feedback::parse_feedback(row) {
+                Ok(states) => states,
+                Err(_) if !rows.is_empty() => break,
+                Err(source) => return Err(ShareError::InvalidRow { line, source }),
+            };
+            if states.len() > MAX_PATTERN_LENGTH {
+                return Err(ShareError::RowTooLong { line, found: states.len(), max: MAX_PATTERN_LENGTH });
+            }
+            if rows.is_empty() {
+                word_length = states.len();
+            } else if states.len() != word_length {
+                return Err(ShareError::RowLength { line, expected: word_length, found: states.len() });
+            }
+            rows.push(Pattern::from_states(&states));
+        }
+
+        let grid: ShareGrid = ShareGrid { puzzle, max_turns, hard_mode, word_length, rows };
+        let solved: Pattern = Pattern::all_correct(word_length);
+        let solved_early: bool = grid.rows.iter().rev().skip(1).any(|&row| row == solved);
+        let expected_rows: usize = solved_in.unwrap_or(max_turns);
+        if grid.rows.is_empty() || solved_early || grid.rows.len() != expected_rows || grid.turns() != solved_in {
+            let score: &str = header.split_whitespace().last().unwrap_or_default();
+            return Err(ShareError::ScoreMismatch { score: score.to_string(), rows: grid.rows.len() });
+        }
+        Ok(grid)
+    }
+
+}
+
+/// Read "Wordle 1,234 4/6*" into the puzzle number, the number of guesses if solved, the turn limit and hard mode
+fn parse_header(header: &str) -> Option<(u32, Option<usize>, usize, bool)> {
+    let mut words = header.split_whitespace();
+    if words.next()? != GAME_NAME {
+        return None;
+    }
+    let puzzle: u32 = words.next()?.replace([',', '.'], "").parse().ok()?;
+    let score: &str = words.next()?;
+    if words.next().is_some() {
+        return None;
+    }
+    let (score, hard_mode): (&str, bool) = match score.strip_suffix('*') {
+        Some(score) => (score, true),
+        None => (score, false),
+    };
+    let (turns, max_turns): (&str, &str) = score.split_once('/')?;
+    let max_turns: usize = max_turns.parse().ok().filter(|&max| max > 0)?;
+    let turns: Option<usize> = match turns {
+        "X" | "x" => None,
+        turns => Some(turns.parse().ok().filter(|&turns| turns > 0 && turns <= max_turns)?),
+    };
+    Some((puzzle, turns, max_turns, hard_mode))
+}
+
+/// Write a puzzle number with thousands separators, e.g. 1,234
+fn format_puzzle(puzzle: u32) -> String {
+    let digits: String = puzzle.to_string();
+    let mut text: String = String::new();
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            text.push(',');
+        }
+        text.push(digit);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+
+    // Local crate imports
+    use super::*;
+
+    /// Results of a game against "caper" solved in four guesses
+    fn create_results_fixture() -> Vec<GuessResult> {
+        ["slate", "crane", "cover", "caper"].iter().map(|guess| GuessResult::evaluate_guess(guess, "caper")).collect()
+    }
+
+    #[test]
+    fn test_share_text_round_trips() {
+        let grid: ShareGrid = ShareGrid::from_results(1234, &create_results_fixture(), 6, true);
+        let text: String = grid.to_text();
+        assert_eq!(text, "Wordle 1,234 4/6*\n\n⬛⬛🟨⬛🟨\n🟩🟨🟨⬛🟨\n🟩⬛⬛🟩🟩\n🟩🟩🟩🟩🟩\n");
+        assert_eq!(ShareGrid::parse(&text), Ok(grid));
+    }
+
+    #[test]
+    fn test_lost_games_and_other_formats() {
+        let rows: Vec<Pattern> = create_results_fixture()[..3].iter().map(|r| r.pattern()).collect();
+        let grid: ShareGrid = ShareGrid::parse(
+            "  Wordle 987 X/3\n⬜⬜🟨⬜🟨\n🟧🟦🟦⬜🟦\n🟧⬜⬜🟧🟧\n\nhttps://www.nytimes.com/games/wordle\n"
+        ).unwrap();
+        assert_eq!(grid.puzzle, 987);
+        assert_eq!(grid.turns(), None);
+        assert!(!grid.hard_mode);
+        assert_eq!(grid.rows, rows);
+        assert_eq!(grid.score(), "X/3");
+        assert!(grid.to_text().starts_with("Wordle 987 X/3\n"));
+        assert!(grid.to_text_with(&EmojiRenderer::light()).ends_with("\n🟩⬜⬜🟩🟩\n"));
+    }
+
+    #[test]
+    fn test_invalid_share_text() {
+        assert_eq!(ShareGrid::parse("\n\n"), Err(ShareError::MissingHeader));
+        assert!(matches!(ShareGrid::parse("Quordle 1 4/9"), Err(ShareError::InvalidHeader { .. })));
+        assert!(matches!(ShareGrid::parse("Wordle 1 7/6"), Err(ShareError::InvalidHeader { .. })));
+        assert_eq!(ShareGrid::parse("Wordle 1 1/6\n🟩🟩🟩").map(|grid| grid.turns()), Ok(Some(1)));
+        assert!(matches!(ShareGrid::parse("Wordle 1 1/6\n🟩🟩z🟩"), Err(ShareError::InvalidRow { line: 2, .. })));
+        assert_eq!(
+            ShareGrid::parse("Wordle 1 2/6\n⬛⬛⬛\n🟩🟩🟩🟩"),
+            Err(ShareError::RowLength { line: 3, expected: 3, found: 4 })
+        );
+        assert_eq!(
+            ShareGrid::parse("Wordle 1 2/6\n🟩🟩🟩\n🟩🟩🟩"),
+            Err(ShareError::ScoreMismatch { score: "2/6".to_string(), rows: 2 })
+        );
+        assert!(matches!(ShareGrid::parse("Wordle 1 X/2\n⬛⬛⬛"), Err(ShareError::ScoreMismatch { .. })));
+        let too_long: String = format!("Wordle 1 1/6\n{}", "🟩".repeat(MAX_PATTERN_LENGTH + 1));
+        assert_eq!(ShareGrid::parse(&too_long), Err(ShareError::RowTooLong { line: 2, found: 21, max: 20 }));
+        assert_eq!(format_puzzle(1234567), "1,234,567");
+        assert_eq!(format_puzzle(12), "12");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialized_grids_are_checked() {
+        let grid: ShareGrid = ShareGrid::from_results(1, &create_results_fixture(), 6, true);
+        let json: String = serde_json::to_string(&grid).unwrap();
+        assert_eq!(serde_json::from_str::<ShareGrid>(&json).unwrap(), grid);
+        // The first row's code is 10, but two letter words only have 9 patterns
+        let invalid: String = json.replace("\"word_length\":5", "\"word_length\":2");
+        let error: String = serde_json::from_str::<ShareGrid>(&invalid).unwrap_err().to_string();
+        assert!(error.contains("Row 1 has invalid pattern code 10"), "Unexpected error: {}", error);
+        let long: String = json.replace("\"word_length\":5", "\"word_length\":21");
+        assert!(serde_json::from_str::<ShareGrid>(&long).unwrap_err().to_string().contains("at most 20"));
+    }
+
+}
@@ -0,0 +1,307 @@
+//! Whole-library simulation.
+//!
+//! Plays a strategy against every answer in a library and summarizes how many guesses it needed.
+
+// Standard library imports
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::thread;
+
+// Local crate imports
+use crate::absurdle::AdversarialHost;
+use crate::game::Game;
+use crate::game::GameStatus;
+use crate::hard_mode;
+use crate::pattern::Pattern;
+use crate::rules::FeedbackRules;
+use crate::rules::NytRules;
+use crate::solver::Solver;
+use crate::solver::Strategy;
+use crate::tree::DecisionTree;
+use crate::GuessResult;
+use crate::Library;
+
+/// Settings for playing a strategy against every answer in a library
+pub struct Simulation {
+    max_turns: usize,
+    rules: Arc<dyn FeedbackRules>,
+    hard_mode: bool,
+}
+
+impl Simulation {
+
+    /// Create a simulation that allows the given number of guesses per game, scored with the New York Times rules
+    pub fn new(max_turns: usize) -> Simulation {
+        Simulation { max_turns, rules: Arc::new(NytRules), hard_mode: false }
+    }
+
+    /// Score guesses with a different rule set
+    pub fn with_rules(self, rules: Arc<dyn FeedbackRules>) -> Simulation {
+        Simulation { rules, ..self }
+    }
+
+    /// Play every game in hard mode
+    pub fn with_hard_mode(self, hard_mode: bool) -> Simulation {
+        Simulation { hard_mode, ..self }
+    }
+
+    /// Play one game against one of the library's answers.
+    /// Returns the number of guesses needed, or None if the game was not solved within the turn limit
+    /// or the answer is not in the library.
+    pub fn play<S: Strategy>(&self, library: &Library, strategy: &S, answer: &str) -> Option<usize> {
+        let mut solver: Solver<&S> = Solver::new(library, strategy)
+            .with_rules(self.rules.clone())
+            .with_hard_mode(self.hard_mode);
+        let mut game: Game = Game::new(library, answer).ok()?
+            .with_max_turns(self.max_turns)
+            .with_rules(self.rules.clone())
+            .with_hard_mode(self.hard_mode);
+        while game.status() == GameStatus::InProgress {
+            let guess: &str = solver.suggest()?;
+            let pattern: Pattern = game.guess(guess).ok()?.pattern();
+            solver.record(GuessResult::from_pattern(guess, pattern));
+        }
+        match game.status() {
+            GameStatus::Won { turns } => Some(turns),
+            _ => None,
+        }
+    }
+
+    /// Play one game against an adversarial host that avoids committing to an answer.
+    /// Returns the number of guesses needed, or None if the game was not won within the turn limit.
+    pub fn play_adversary<S: Strategy>(&self, library: &Library, strategy: &S) -> Option<usize> {
+        let mut solver: Solver<&S> = Solver::new(library, strategy)
+            .with_rules(self.rules.clone())
+            .with_hard_mode(self.hard_mode);
+        let mut host: AdversarialHost = AdversarialHost::new(library).with_rules(self.rules.clone());
+        for turn in 1..=self.max_turns {
+            let guess: &str = solver.suggest()?;
+            let result: GuessResult = GuessResult::from_pattern(guess, host.respond(guess).pattern());
+            if host.is_won() {
+                return Some(turn);
+            }
+            solver.record(result);
+        }
+        None
+    }
+
+    /// Play a game against every answer in the library, spreading the games across all available threads
+    pub fn run<S: Strategy + Sync>(&self, library: &Library, strategy: &S) -> SimulationReport {
+        let threads: usize = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        let answers_per_thread: usize = library.answers.len().div_ceil(threads).max(1);
+        let outcomes: Vec<Option<usize>> = thread::scope(|scope| {
+            let handles: Vec<_> = library.answers.chunks(answers_per_thread).map(|answers| {
+                scope.spawn(move || {
+                    answers.iter().map(|answer| self.play(library, strategy, answer)).collect::<Vec<_>>()
+                })
+            }).collect();
+            handles.into_iter().flat_map(|handle| handle.join().expect("Simulation thread panicked")).collect()
+        });
+        SimulationReport::from_outcomes(self.max_turns, &library.answers, &outcomes)
+    }
+
+    /// Play every answer by looking guesses up in a decision tree, without running a strategy
+    pub fn run_tree(&self, library: &Library, tree: &DecisionTree) -> SimulationReport {
+        let outcomes: Vec<Option<usize>> = library.answers.iter()
+            .map(|answer| tree.guesses_to_solve_with(self.rules.as_ref(), answer))
+            .map(|turns| turns.filter(|&turns| turns <= self.max_turns))
+            .collect();
+        SimulationReport::from_outcomes(self.max_turns, &library.answers, &outcomes)
+    }
+
+    /// Record the guesses a strategy makes against every answer as a decision tree.
+    /// Returns None if the strategy fails to solve some answer within the turn limit.
+    pub fn decision_tree<S: Strategy>(&self, library: &Library, strategy: &S) -> Option<DecisionTree> {
+        let guesses: Vec<&str> = library.guesses.iter().map(|w| w.as_str()).collect();
+        let candidates: Vec<&str> = library.answers.iter().map(|w| w.as_str()).collect();
+        self.grow_tree(strategy, &guesses, candidates, &mut Vec::new(), self.max_turns)
+    }
+
+    /// Build the subtree for the candidates left after the guess results in history
+    fn grow_tree<S: Strategy>(
+        &self,
+        strategy: &S,
+        guesses: &[&str],
+        candidates: Vec<&str>,
+        history: &mut Vec<GuessResult>,
+        turns_left: usize,
+    ) -> Option<DecisionTree> {
+        if turns_left == 0 {
+            return None;
+        }
+        let guess: &str = if self.hard_mode {
+            strategy.choose_guess(&hard_mode::hard_mode_guesses(guesses, history), &candidates)?
+        } else {
+            strategy.choose_guess(guesses, &candidates)?
+        };
+        let mut buckets: BTreeMap<Pattern, Vec<&str>> = BTreeMap::new();
+        for &answer in candidates.iter().filter(|&&answer| answer != guess) {
+            buckets.entry(Pattern::evaluate_with(self.rules.as_ref(), guess, answer)).or_default().push(answer);
+        }
+        let mut branches: BTreeMap<Pattern, DecisionTree> = BTreeMap::new();
+        for (pattern, bucket) in buckets {
+            history.push(GuessResult::from_pattern(guess, pattern));
+            let branch: Option<DecisionTree> = self.grow_tree(strategy, guesses, bucket, history, turns_left - 1);
+            history.pop();
+            branches.insert(pattern, branch?);
+        }
+        Some(DecisionTree { guess: guess.to_string(), is_answer: candidates.contains(&guess), branches })
+    }
+
+}
+
+/// Summary of a strategy's performance over every answer in a library
+#[derive(Debug)]
+pub struct SimulationReport {
+
+    /// Guess limit each game was played with
+    pub max_turns: usize,
+
+    /// Number of games solved in each number of guesses; index 0 counts games solved in one guess
+    pub distribution: Vec<usize>,
+
+    /// Answers that were not solved within the guess limit
+    pub failures: Vec<String>,
+
+    /// Answers that needed the most guesses among the solved games
+    pub worst: Vec<String>,
+}
+
+impl SimulationReport {
+
+    /// Summarize the outcome of each game, in the same order as the answers
+    fn from_outcomes(max_turns: usize, answers: &[String], outcomes: &[Option<usize>]) -> SimulationReport {
+        let mut distribution: Vec<usize> = vec![0; max_turns];
+        let mut failures: Vec<String> = Vec::new();
+        for (answer, outcome) in answers.iter().zip(outcomes) {
+            match outcome {
+                Some(turns) => distribution[turns - 1] += 1,
+                None => failures.push(answer.clone()),
+            }
+        }
+        let most: Option<usize> = outcomes.iter().flatten().copied().max();
+        let worst: Vec<String> = answers.iter().zip(outcomes)
+            .filter(|(_, outcome)| outcome.is_some() && **outcome == most)
+            .map(|(answer, _)| answer.clone())
+            .collect();
+        SimulationReport { max_turns, distribution, failures, worst }
+    }
+
+    /// Number of games played
+    pub fn games(&self) -> usize {
+        self.solved() + self.failures.len()
+    }
+
+    /// Number of games solved within the guess limit
+    pub fn solved(&self) -> usize {
+        self.distribution.iter().sum()
+    }
+
+    /// Mean number of guesses over the solved games
+    pub fn mean(&self) -> f64 {
+        let total: usize = self.distribution.iter().enumerate().map(|(i, count)| (i + 1) * count).sum();
+        total as f64 / self.solved().max(1) as f64
+    }
+
+    /// Most guesses needed by any solved game
+    pub fn worst_turns(&self) -> Option<usize> {
+        self.distribution.iter().rposition(|&count| count > 0).map(|i| i + 1)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    // Local crate imports
+    use super::*;
+    use crate::solver::EntropyStrategy;
+    use crate::solver::MinimaxStrategy;
+    use crate::tests::create_word_library;
+    use crate::tests::REPEATED_LETTER_WORDS;
+
+    /// Strategy that always guesses the first candidate
+    struct FirstCandidate;
+
+    impl Strategy for FirstCandidate {
+
+        fn choose_guess<'a>(&self, _guesses: &[&'a str], candidates: &[&'a str]) -> Option<&'a str> {
+            candidates.first().copied()
+        }
+
+    }
+
+    #[test]
+    fn test_simulation_plays_every_answer() {
+        let library: Library = create_word_library(&REPEATED_LETTER_WORDS, &[]);
+        let report: SimulationReport = Simulation::new(6).run(&library, &EntropyStrategy::new());
+        assert_eq!(report.games(), library.answers.len());
+        assert!(report.failures.is_empty());
+        assert!(report.mean() >= 1.0);
+        let worst_turns: usize = report.worst_turns().unwrap();
+        for answer in &report.worst {
+            assert_eq!(Simulation::new(6).play(&library, &EntropyStrategy::new(), answer), Some(worst_turns));
+        }
+    }
+
+    #[test]
+    fn test_decision_tree_replays_simulation() {
+        let library: Library = create_word_library(&REPEATED_LETTER_WORDS, &[]);
+        let simulation: Simulation = Simulation::new(6);
+        let tree: DecisionTree = simulation.decision_tree(&library, &EntropyStrategy::new()).unwrap();
+        assert_eq!(tree.answer_count(), library.answers.len());
+        let played: SimulationReport = simulation.run(&library, &EntropyStrategy::new());
+        let looked_up: SimulationReport = simulation.run_tree(&library, &tree);
+        assert_eq!(looked_up.distribution, played.distribution);
+        assert!(Simulation::new(1).decision_tree(&library, &EntropyStrategy::new()).is_none());
+    }
+
+    #[test]
+    fn test_simulation_supports_other_lengths() {
+        for word_length in [4, 6, 7] {
+            let library: Library = crate::tests::create_generated_library(word_length, 40);
+            let simulation: Simulation = Simulation::new(10);
+            for report in [
+                simulation.run(&library, &EntropyStrategy::new()),
+                simulation.run(&library, &MinimaxStrategy::new()),
+            ] {
+                assert_eq!(report.games(), library.answers.len());
+                assert!(report.failures.is_empty(), "{}-letter games failed: {:?}", word_length, report.failures);
+            }
+            let tree: DecisionTree = simulation.decision_tree(&library, &EntropyStrategy::new()).unwrap();
+            assert_eq!(DecisionTree::parse(&tree.to_text()).unwrap(), tree);
+        }
+    }
+
+    #[test]
+    fn test_adversary_game_replays_its_final_answer() {
+        let library: Library = create_word_library(&REPEATED_LETTER_WORDS, &[]);
+        let simulation: Simulation = Simulation::new(6);
+        let worst: usize = simulation.run(&library, &EntropyStrategy::new()).worst_turns().unwrap();
+        let turns: usize = simulation.play_adversary(&library, &EntropyStrategy::new()).unwrap();
+        assert!(turns <= worst);
+
+        // Every reply matches the answer the host settles on, so playing that answer takes as many turns
+        let mut solver: Solver<EntropyStrategy> = Solver::new(&library, EntropyStrategy::new());
+        let mut host: AdversarialHost = AdversarialHost::new(&library);
+        while !host.is_won() {
+            let guess: String = solver.suggest().unwrap().to_string();
+            let result: GuessResult = host.respond(&guess).clone();
+            solver.record(result);
+        }
+        assert_eq!(host.candidates().len(), 1);
+        assert_eq!(simulation.play(&library, &EntropyStrategy::new(), host.candidates()[0]), Some(turns));
+        assert_eq!(Simulation::new(1).play_adversary(&library, &FirstCandidate), None);
+    }
+
+    #[test]
+    fn test_simulation_reports_failures() {
+        let library: Library = create_word_library(&REPEATED_LETTER_WORDS, &[]);
+        let report: SimulationReport = Simulation::new(1).run(&library, &FirstCandidate);
+        assert_eq!(report.distribution, vec![1]);
+        assert_eq!(report.failures.len(), library.answers.len() - 1);
+        assert_eq!(report.worst, vec!["crane"]);
+        assert_eq!(report.mean(), 1.0);
+    }
+
+}
@@ -0,0 +1,381 @@
+//! Guess selection.
+//!
+//! Provides strategies for choosing the next guess and a solver that tracks the state of a game.
+
+// Standard library imports
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+// Local crate imports
+use crate::filter;
+use crate::hard_mode;
+use crate::hard_mode::HardModeViolation;
+use crate::matrix::PatternMatrix;
+use crate::pattern;
+use crate::rules::FeedbackRules;
+use crate::rules::NytRules;
+use crate::GuessResult;
+use crate::Library;
+
+/// A way of choosing the next guess
+pub trait Strategy {
+
+    /// Choose the next guess from a pool of allowed guesses, given the answers that are still possible.
+    /// Returns None if there are no guesses or no candidates.
+    fn choose_guess<'a>(&self, guesses: &[&'a str], candidates: &[&'a str]) -> Option<&'a str>;
+
+}
+
+impl<S: Strategy + ?Sized> Strategy for &S {
+
+    fn choose_guess<'a>(&self, guesses: &[&'a str], candidates: &[&'a str]) -> Option<&'a str> {
+        (**self).choose_guess(guesses, candidates)
+    }
+
+}
+
+impl<S: Strategy + ?Sized> Strategy for Box<S> {
+
+    fn choose_guess<'a>(&self, guesses: &[&'a str], candidates: &[&'a str]) -> Option<&'a str> {
+        (**self).choose_guess(guesses, candidates)
+    }
+
+}
+
+/// Strategy that picks the guess whose feedback is expected to reveal the most information
+pub struct EntropyStrategy {
+    rules: Arc<dyn FeedbackRules>,
+    matrix: Option<Arc<PatternMatrix>>,
+}
+
+impl Default for EntropyStrategy {
+
+    fn default() -> EntropyStrategy {
+        EntropyStrategy::new()
+    }
+
+}
+
+impl EntropyStrategy {
+
+    /// Create a strategy that evaluates patterns with the New York Times rules as it needs them
+    pub fn new() -> EntropyStrategy {
+        EntropyStrategy { rules: Arc::new(NytRules), matrix: None }
+    }
+
+    /// Create a strategy that looks patterns up in a precomputed matrix.
+    /// Guesses or candidates missing from the matrix are evaluated directly.
+    pub fn with_matrix(matrix: Arc<PatternMatrix>) -> EntropyStrategy {
+        EntropyStrategy { matrix: Some(matrix), ..EntropyStrategy::new() }
+    }
+
+    /// Evaluate patterns with a different rule set.
+    /// Any matrix given to the strategy should have been computed with the same rules.
+    pub fn with_rules(self, rules: Arc<dyn FeedbackRules>) -> EntropyStrategy {
+        EntropyStrategy { rules, ..self }
+    }
+
+    /// Score every guess by expected information, best first.
+    /// Ties are broken in favour of guesses that could be the answer, then by pool order.
+    pub fn rank_guesses<'a>(&self, guesses: &[&'a str], candidates: &[&'a str]) -> Vec<(&'a str, f64)> {
+        let mut ranked: Vec<(&'a str, f64, bool)> = Vec::with_capacity(guesses.len());
+        for_each_bucket_sizes(self.rules.as_ref(), self.matrix.as_deref(), guesses, candidates, |guess, sizes| {
+            ranked.push((guess, entropy_from_counts(sizes, candidates.len()), candidates.contains(&guess)));
+        });
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| prefer_candidates(a.2, b.2)));
+        ranked.into_iter().map(|(guess, score, _)| (guess, score)).collect()
+    }
+
+}
+
+impl Strategy for EntropyStrategy {
+
+    fn choose_guess<'a>(&self, guesses: &[&'a str], candidates: &[&'a str]) -> Option<&'a str> {
+        match candidates {
+            [] => None,
+            [only] => Some(*only),
+            _ => self.rank_guesses(guesses, candidates).first().map(|(guess, _)| *guess),
+        }
+    }
+
+}
+
+/// Strategy that picks the guess whose worst-case feedback leaves the fewest candidates
+pub struct MinimaxStrategy {
+    rules: Arc<dyn FeedbackRules>,
+    matrix: Option<Arc<PatternMatrix>>,
+}
+
+impl Default for MinimaxStrategy {
+
+    fn default() -> MinimaxStrategy {
+        MinimaxStrategy::new()
+    }
+
+}
+
+impl MinimaxStrategy {
+
+    /// Create a strategy that evaluates patterns with the New York Times rules as it needs them
+    pub fn new() -> MinimaxStrategy {
+        MinimaxStrategy { rules: Arc::new(NytRules), matrix: None }
+    }
+
+    /// Create a strategy that looks patterns up in a precomputed matrix.
+    /// Guesses or candidates missing from the matrix are evaluated directly.
+    pub fn with_matrix(matrix: Arc<PatternMatrix>) -> MinimaxStrategy {
+        MinimaxStrategy { matrix: Some(matrix), ..MinimaxStrategy::new() }
+    }
+
+    /// Evaluate patterns with a different rule set.
+    /// Any matrix given to the strategy should have been computed with the same rules.
+    pub fn with_rules(self, rules: Arc<dyn FeedbackRules>) -> MinimaxStrategy {
+        MinimaxStrategy { rules, ..self }
+    }
+
+    /// Score every guess by the size of its largest pattern bucket, best first.
+    /// Ties are broken in favour of guesses that could be the answer, then by the number of distinct
+    /// patterns, then by pool order.
+    pub fn rank_guesses<'a>(&self, guesses: &[&'a str], candidates: &[&'a str]) -> Vec<(&'a str, usize)> {
+        let mut ranked: Vec<(&'a str, usize, bool, usize)> = Vec::with_capacity(guesses.len());
+        for_each_bucket_sizes(self.rules.as_ref(), self.matrix.as_deref(), guesses, candidates, |guess, sizes| {
+            let largest: usize = sizes.iter().copied().max().unwrap_or(0);
+            ranked.push((guess, largest, candidates.contains(&guess), sizes.len()));
+        });
+        ranked.sort_by(|a, b| {
+            a.1.cmp(&b.1).then_with(|| prefer_candidates(a.2, b.2)).then_with(|| b.3.cmp(&a.3))
+        });
+        ranked.into_iter().map(|(guess, largest, _, _)| (guess, largest)).collect()
+    }
+
+}
+
+impl Strategy for MinimaxStrategy {
+
+    fn choose_guess<'a>(&self, guesses: &[&'a str], candidates: &[&'a str]) -> Option<&'a str> {
+        match candidates {
+            [] => None,
+            [only] => Some(*only),
+            _ => self.rank_guesses(guesses, candidates).first().map(|(guess, _)| *guess),
+        }
+    }
+
+}
+
+/// Size the pattern buckets candidates fall into for every guess, using a matrix where it covers the words
+pub(crate) fn for_each_bucket_sizes<'a>(
+    rules: &dyn FeedbackRules,
+    matrix: Option<&PatternMatrix>,
+    guesses: &[&'a str],
+    candidates: &[&str],
+    mut visit: impl FnMut(&'a str, &[usize]),
+) {
+    let matrix: Option<(&PatternMatrix, Vec<usize>)> = matrix.and_then(|m| Some((m, m.answer_indices(candidates)?)));
+    for &guess in guesses {
+        let sizes: Vec<usize> = matrix.as_ref()
+            .and_then(|(m, indices)| Some(m.bucket_sizes(m.guess_index(guess)?, indices)))
+            .unwrap_or_else(|| pattern::bucket_sizes_with(rules, guess, candidates));
+        visit(guess, &sizes);
+    }
+}
+
+/// Order guesses that could be the answer before guesses that cannot
+pub(crate) fn prefer_candidates(a_is_candidate: bool, b_is_candidate: bool) -> Ordering {
+    b_is_candidate.cmp(&a_is_candidate)
+}
+
+/// Shannon entropy (in bits) of the feedback patterns a guess produces over the candidate answers
+pub fn entropy(guess: &str, candidates: &[&str]) -> f64 {
+    entropy_from_counts(&pattern::bucket_sizes(guess, candidates), candidates.len())
+}
+
+/// Shannon entropy (in bits) of a distribution of candidates over pattern buckets
+pub fn entropy_from_counts(counts: &[usize], total: usize) -> f64 {
+    let total: f64 = total as f64;
+    counts.iter().filter(|&&count| count > 0).map(|&count| {
+        let p: f64 = count as f64 / total;
+        -p * p.log2()
+    }).sum()
+}
+
+/// Tracks the candidates remaining in a game and suggests guesses with a strategy
+pub struct Solver<'a, S: Strategy> {
+    strategy: S,
+    rules: Arc<dyn FeedbackRules>,
+    hard_mode: bool,
+    guesses: Vec<&'a str>,
+    candidates: Vec<&'a str>,
+    history: Vec<GuessResult>,
+}
+
+impl<'a, S: Strategy> Solver<'a, S> {
+
+    /// Create a solver for a fresh game using every guess and answer in the library
+    pub fn new(library: &'a Library, strategy: S) -> Solver<'a, S> {
+        Solver {
+            strategy,
+            rules: Arc::new(NytRules),
+            hard_mode: false,
+            guesses: library.guesses.iter().map(|w| w.as_str()).collect(),
+            candidates: library.answers.iter().map(|w| w.as_str()).collect(),
+            history: Vec::new(),
+        }
+    }
+
+    /// Narrow candidates with a different rule set.
+    /// The strategy should be scoring guesses with the same rules.
+    pub fn with_rules(self, rules: Arc<dyn FeedbackRules>) -> Solver<'a, S> {
+        Solver { rules, ..self }
+    }
+
+    /// Only suggest guesses that use every hint revealed so far
+    pub fn with_hard_mode(self, hard_mode: bool) -> Solver<'a, S> {
+        Solver { hard_mode, ..self }
+    }
+
+    /// Check that a guess is allowed, which only restricts guesses in hard mode
+    pub fn check_guess(&self, guess: &str) -> Result<(), HardModeViolation> {
+        if self.hard_mode {
+            return hard_mode::check_hard_mode(guess, &self.history);
+        }
+        Ok(())
+    }
+
+    /// Suggest the next guess
+    pub fn suggest(&self) -> Option<&'a str> {
+        if self.hard_mode {
+            let guesses: Vec<&'a str> = hard_mode::hard_mode_guesses(&self.guesses, &self.history);
+            return self.strategy.choose_guess(&guesses, &self.candidates);
+        }
+        self.strategy.choose_guess(&self.guesses, &self.candidates)
+    }
+
+    /// Record the feedback for a guess and narrow the candidates
+    pub fn record(&mut self, result: GuessResult) {
+        self.candidates = filter::narrow_with(self.rules.as_ref(), &self.candidates, &result);
+        self.history.push(result);
+    }
+
+    /// Answers that are still possible
+    pub fn candidates(&self) -> &[&'a str] {
+        &self.candidates
+    }
+
+    /// Guess results recorded so far
+    pub fn history(&self) -> &[GuessResult] {
+        &self.history
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    // Local crate imports
+    use super::*;
+    use crate::tests::create_word_library;
+    use crate::tests::SMALL_LIBRARY_WORDS;
+
+    #[test]
+    fn test_entropy_of_uninformative_guess_is_zero() {
+        assert_eq!(entropy("zzzzz", &["crane", "slate", "trace"]), 0.0);
+    }
+
+    #[test]
+    fn test_entropy_of_perfect_split() {
+        let candidates: [&str; 4] = ["crane", "slate", "plumb", "brick"];
+        // Each candidate produces a different pattern for this guess
+        assert!((entropy("crane", &candidates) - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rank_prefers_informative_guesses() {
+        let library: Library = create_word_library(&SMALL_LIBRARY_WORDS, &["zzzzz"]);
+        let solver: Solver<EntropyStrategy> = Solver::new(&library, EntropyStrategy::new());
+        let ranked: Vec<(&str, f64)> = EntropyStrategy::new().rank_guesses(&solver.guesses, solver.candidates());
+        assert_eq!(ranked.last().map(|(guess, _)| *guess), Some("zzzzz"));
+        assert!(ranked.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    }
+
+    #[test]
+    fn test_solver_finds_every_answer() {
+        let library: Library = create_word_library(&SMALL_LIBRARY_WORDS, &["zzzzz"]);
+        for answer in &library.answers {
+            let mut solver: Solver<EntropyStrategy> = Solver::new(&library, EntropyStrategy::new());
+            let mut solved: bool = false;
+            for _ in 0..6 {
+                let guess: &str = solver.suggest().expect("Solver should always have a suggestion");
+                if guess == answer {
+                    solved = true;
+                    break;
+                }
+                solver.record(GuessResult::evaluate_guess(guess, answer));
+            }
+            assert!(solved, "Solver failed to find {}", answer);
+        }
+    }
+
+    #[test]
+    fn test_minimax_minimizes_largest_bucket() {
+        let library: Library = create_word_library(&SMALL_LIBRARY_WORDS, &["zzzzz"]);
+        let solver: Solver<MinimaxStrategy> = Solver::new(&library, MinimaxStrategy::new());
+        let ranked: Vec<(&str, usize)> = MinimaxStrategy::new().rank_guesses(&solver.guesses, solver.candidates());
+        assert_eq!(ranked.last(), Some(&("zzzzz", library.answers.len())));
+        assert!(ranked.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        for (guess, largest) in &ranked {
+            let sizes: Vec<usize> = pattern::bucket_sizes(guess, solver.candidates());
+            assert_eq!(sizes.iter().max(), Some(largest));
+        }
+    }
+
+    #[test]
+    fn test_minimax_prefers_possible_answers() {
+        // Every guess splits these two candidates apart, so a candidate should be chosen
+        let candidates: [&str; 2] = ["plumb", "brick"];
+        let guesses: [&str; 3] = ["crane", "slate", "brick"];
+        assert_eq!(MinimaxStrategy::new().choose_guess(&guesses, &candidates), Some("brick"));
+    }
+
+    #[test]
+    fn test_minimax_solver_finds_every_answer() {
+        let library: Library = create_word_library(&SMALL_LIBRARY_WORDS, &["zzzzz"]);
+        for answer in &library.answers {
+            let mut solver: Solver<MinimaxStrategy> = Solver::new(&library, MinimaxStrategy::new());
+            let mut turns: usize = 0;
+            while let Some(guess) = solver.suggest() {
+                turns += 1;
+                if guess == answer {
+                    break;
+                }
+                solver.record(GuessResult::evaluate_guess(guess, answer));
+            }
+            assert!(turns <= 6, "Minimax needed {} guesses for {}", turns, answer);
+        }
+    }
+
+    #[test]
+    fn test_hard_mode_suggestions_use_hints() {
+        let library: Library = create_word_library(&SMALL_LIBRARY_WORDS, &["zzzzz"]);
+        for answer in &library.answers {
+            let mut solver: Solver<EntropyStrategy> = Solver::new(&library, EntropyStrategy::new()).with_hard_mode(true);
+            while let Some(guess) = solver.suggest() {
+                assert_eq!(hard_mode::check_hard_mode(guess, solver.history()), Ok(()));
+                if guess == answer {
+                    break;
+                }
+                solver.record(GuessResult::evaluate_guess(guess, answer));
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_ranking_matches_direct_ranking() {
+        let library: Library = create_word_library(&SMALL_LIBRARY_WORDS, &["zzzzz"]);
+        let matrix: Arc<PatternMatrix> = Arc::new(PatternMatrix::compute(&library));
+        let solver: Solver<EntropyStrategy> = Solver::new(&library, EntropyStrategy::new());
+        let direct: Vec<(&str, f64)> = EntropyStrategy::new().rank_guesses(&solver.guesses, solver.candidates());
+        let cached: Vec<(&str, f64)> = EntropyStrategy::with_matrix(matrix).rank_guesses(&solver.guesses, solver.candidates());
+        assert_eq!(direct, cached);
+    }
+
+}
@@ -0,0 +1,323 @@
+//! Decision trees.
+//!
+//! A decision tree records a complete strategy: the guess to play, and for every feedback pattern
+//! that guess can produce, the tree to follow next. Trees can be saved as text and played back by
+//! lookup alone, without running a strategy.
+//!
+//! The text format has one guess per line, indented two spaces per level. Every line below the root
+//! starts with the feedback that leads to it, written with G for Correct, Y for Present and B for
+//! Absent. Guesses that cannot be the answer at that point are written in parentheses:
+//!
+//! ```text
+//! crane
+//!   BBBBB (hoist)
+//!     BBBBB plumb
+//!   BBGBG slate
+//! ```
+
+// Standard library imports
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+// Local crate imports
+use crate::pattern::Pattern;
+use crate::pattern::MAX_PATTERN_LENGTH;
+use crate::rules::FeedbackRules;
+use crate::rules::NytRules;
+use crate::GuessResult;
+use crate::LetterState;
+
+/// A guess and the subtree to follow for each pattern it can produce
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DecisionTree {
+
+    /// Word to guess at this point
+    pub guess: String,
+
+    /// Whether the guess is itself one of the remaining answers, i.e. whether it can win here
+    pub is_answer: bool,
+
+    /// Subtree for each pattern the guess can produce, excluding the all-correct pattern
+    pub branches: BTreeMap<Pattern, DecisionTree>,
+}
+
+impl DecisionTree {
+
+    /// Create a tree that guesses the only remaining answer
+    pub fn leaf(answer: &str) -> DecisionTree {
+        DecisionTree { guess: answer.to_string(), is_answer: true, branches: BTreeMap::new() }
+    }
+
+    /// Subtree to follow after the guess at this point produced a pattern
+    pub fn branch(&self, pattern: Pattern) -> Option<&DecisionTree> {
+        self.branches.get(&pattern)
+    }
+
+    /// Number of answers the tree solves
+    pub fn answer_count(&self) -> usize {
+        self.is_answer as usize + self.branches.values().map(|tree| tree.answer_count()).sum::<usize>()
+    }
+
+    /// Total number of guesses needed to solve every answer in the tree
+    pub fn total_guesses(&self) -> usize {
+        self.answer_count() + self.branches.values().map(|tree| tree.total_guesses()).sum::<usize>()
+    }
+
+    /// Mean number of guesses needed per answer
+    pub fn average_guesses(&self) -> f64 {
+        self.total_guesses() as f64 / self.answer_count().max(1) as f64
+    }
+
+    /// Most guesses needed by any answer in the tree
+    pub fn depth(&self) -> usize {
+        1 + self.branches.values().map(|tree| tree.depth()).max().unwrap_or(0)
+    }
+
+    /// Next guess to play after the given guess results, found by following the tree from the root.
+    /// Returns None if a result leaves the tree: a different guess was played, or the feedback has no branch.
+    pub fn next_guess(&self, history: &[GuessResult]) -> Option<&str> {
+        let mut node: &DecisionTree = self;
+        for result in history {
+            if result.guess != node.guess {
+                return None;
+            }
+            node = node.branch(result.pattern())?;
+        }
+        Some(&node.guess)
+    }
+
+    /// Number of guesses the tree needs to solve an answer, scored with the New York Times rules.
+    /// Returns None if the tree does not solve the answer.
+    pub fn guesses_to_solve(&self, answer: &str) -> Option<usize> {
+        self.guesses_to_solve_with(&NytRules, answer)
+    }
+
+    /// Number of guesses the tree needs to solve an answer, scored with the given rules
+    pub fn guesses_to_solve_with(&self, rules: &dyn FeedbackRules, answer: &str) -> Option<usize> {
+        let mut node: &DecisionTree = self;
+        let mut turns: usize = 1;
+        while node.guess != answer {
+            node = node.branch(Pattern::evaluate_with(rules, &node.guess, answer))?;
+            turns += 1;
+        }
+        Some(turns)
+    }
+
+    /// Write the tree in its text format
+    pub fn to_text(&self) -> String {
+        let mut text: String = String::new();
+        self.write_text(None, 0, &mut text);
+        text
+    }
+
+    /// Read a tree from its text format
+    pub fn parse(text: &str) -> Result<DecisionTree, TreeError> {
+        // Nodes on the path from the root to the current line, each with the pattern leading to it
+        let mut path: Vec<(Option<Pattern>, DecisionTree)> = Vec::new();
+        let mut root: Option<DecisionTree> = None;
+        for (index, line) in text.lines().enumerate() {
+            let syntax_error = || TreeError::Syntax { line: index + 1, text: line.to_string() };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let content: &str = line.trim_start_matches(' ');
+            let indent: usize = line.len() - content.len();
+            if !indent.is_multiple_of(2) || indent / 2 > path.len() {
+                return Err(syntax_error());
+            }
+            while path.len() > indent / 2 {
+                attach(&mut path, &mut root);
+            }
+            if root.is_some() {
+                return Err(syntax_error());
+            }
+            let (pattern, word): (Option<Pattern>, &str) = match (path.last(), content.split_once(' ')) {
+                (None, None) => (None, content),
+                (Some((_, parent)), Some((feedback, word))) => {
+                    let pattern: Pattern = parse_pattern(feedback, parent.guess.chars().count())
+                        .filter(|pattern| !parent.branches.contains_key(pattern))
+                        .ok_or_else(syntax_error)?;
+                    (Some(pattern), word)
+                },
+                _ => return Err(syntax_error()),
+            };
+            let (guess, is_answer): (&str, bool) = match word.strip_prefix('(').and_then(|w| w.strip_suffix(')')) {
+                Some(guess) => (guess, false),
+                None => (word, true),
+            };
+            if guess.is_empty() || guess.contains(char::is_whitespace) {
+                return Err(syntax_error());
+            }
+            let node: DecisionTree = DecisionTree { guess: guess.to_string(), is_answer, branches: BTreeMap::new() };
+            path.push((pattern, node));
+        }
+        while !path.is_empty() {
+            attach(&mut path, &mut root);
+        }
+        root.ok_or(TreeError::Empty)
+    }
+
+    /// Save the tree to a file in its text format
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+
+    /// Load a tree saved in its text format
+    pub fn load(path: &Path) -> Result<DecisionTree, TreeError> {
+        let text: String = fs::read_to_string(path).map_err(|source| {
+            TreeError::Io { path: path.to_path_buf(), source }
+        })?;
+        DecisionTree::parse(&text)
+    }
+
+    /// Append this node and its branches to the text format
+    fn write_text(&self, pattern: Option<Pattern>, level: usize, text: &mut String) {
+        text.push_str(&"  ".repeat(level));
+        if let Some(pattern) = pattern {
+            text.extend(pattern.to_states(self.guess.chars().count()).iter().map(|state| match state {
+                LetterState::Correct => 'G',
+                LetterState::Present => 'Y',
+                LetterState::Absent => 'B',
+            }));
+            text.push(' ');
+        }
+        if self.is_answer {
+            text.push_str(&self.guess);
+        } else {
+            text.push_str(&format!("({})", self.guess));
+        }
+        text.push('\n');
+        for (&pattern, branch) in &self.branches {
+            branch.write_text(Some(pattern), level + 1, text);
+        }
+    }
+
+}
+
+/// Error reading a decision tree
+#[derive(Debug)]
+pub enum TreeError {
+
+    /// The file could not be read
+    Io { path: PathBuf, source: io::Error },
+
+    /// The text contains no tree
+    Empty,
+
+    /// A line is not a valid node. Lines are numbered from one.
+    Syntax { line: usize, text: String },
+}
+
+impl fmt::Display for TreeError {
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TreeError::Io { path, source } => {
+                write!(f, "Something went wrong reading the file {}: {}", path.display(), source)
+            },
+            TreeError::Empty => write!(f, "No decision tree found"),
+            TreeError::Syntax { line, text } => write!(f, "Invalid decision tree line {}: {:?}", line, text),
+        }
+    }
+
+}
+
+impl Error for TreeError {
+
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TreeError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+
+}
+
+/// Pop the deepest node on the path and attach it to its parent, or make it the root
+fn attach(path: &mut Vec<(Option<Pattern>, DecisionTree)>, root: &mut Option<DecisionTree>) {
+    let (pattern, node): (Option<Pattern>, DecisionTree) = path.pop().expect("Path must not be empty");
+    match (pattern, path.last_mut()) {
+        (Some(pattern), Some((_, parent))) => {
+            parent.branches.insert(pattern, node);
+        },
+        _ => *root = Some(node),
+    }
+}
+
+/// Read feedback written as G, Y and B for a word of the given length
+fn parse_pattern(feedback: &str, word_length: usize) -> Option<Pattern> {
+    let states: Vec<LetterState> = feedback.chars().map(|c| match c {
+        'G' => Some(LetterState::Correct),
+        'Y' => Some(LetterState::Present),
+        'B' => Some(LetterState::Absent),
+        _ => None,
+    }).collect::<Option<_>>()?;
+    (states.len() == word_length && word_length <= MAX_PATTERN_LENGTH).then(|| Pattern::from_states(&states))
+}
+
+#[cfg(test)]
+mod tests {
+
+    // Local crate imports
+    use super::*;
+
+    #[test]
+    fn test_tree_metrics() {
+        // Guess "crane": solves crane in 1, then "slate" solves slate in 2, then "plumb" in 3
+        let mut slate: DecisionTree = DecisionTree::leaf("slate");
+        slate.branches.insert(Pattern::from_code(0), DecisionTree::leaf("plumb"));
+        let mut root: DecisionTree = DecisionTree::leaf("crane");
+        root.branches.insert(Pattern::from_code(1), slate);
+        assert_eq!(root.answer_count(), 3);
+        assert_eq!(root.total_guesses(), 1 + 2 + 3);
+        assert_eq!(root.average_guesses(), 2.0);
+        assert_eq!(root.depth(), 3);
+        assert_eq!(root.branch(Pattern::from_code(1)).map(|tree| tree.guess.as_str()), Some("slate"));
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let mut probe: DecisionTree = DecisionTree::leaf("hoist");
+        probe.is_answer = false;
+        probe.branches.insert(Pattern::evaluate("hoist", "plumb"), DecisionTree::leaf("plumb"));
+        probe.branches.insert(Pattern::evaluate("hoist", "lousy"), DecisionTree::leaf("lousy"));
+        let mut root: DecisionTree = DecisionTree::leaf("crane");
+        root.branches.insert(Pattern::evaluate("crane", "plumb"), probe);
+        root.branches.insert(Pattern::evaluate("crane", "slate"), DecisionTree::leaf("slate"));
+        let text: String = root.to_text();
+        assert_eq!(text, "crane\n  BBBBB (hoist)\n    BBBBB plumb\n    BGBGB lousy\n  BBGBG slate\n");
+        assert_eq!(DecisionTree::parse(&text).unwrap(), root);
+        assert_eq!(root.guesses_to_solve("lousy"), Some(3));
+        assert_eq!(root.guesses_to_solve("eerie"), None);
+        let history: Vec<GuessResult> = vec![GuessResult::evaluate_guess("crane", "lousy")];
+        assert_eq!(root.next_guess(&history), Some("hoist"));
+        assert_eq!(root.next_guess(&[GuessResult::evaluate_guess("slate", "lousy")]), None);
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_trees() {
+        assert!(matches!(DecisionTree::parse(""), Err(TreeError::Empty)));
+        // Feedback for words longer than patterns support cannot be stored
+        let long: String = format!("{}\n  {} {}\n", "a".repeat(21), "B".repeat(21), "b".repeat(21));
+        for (text, line) in [
+            (long.as_str(), 2),
+            ("crane\n    BBBBB plumb\n", 2),
+            ("crane\n  BBBB plumb\n", 2),
+            ("crane\n  BBBBB plumb\n  BBBBB brick\n", 3),
+            ("crane\nslate\n", 2),
+            ("crane\n  plumb\n", 2),
+        ] {
+            match DecisionTree::parse(text) {
+                Err(TreeError::Syntax { line: found, .. }) => assert_eq!(found, line, "{:?}", text),
+                other => panic!("Expected a syntax error for {:?}, got {:?}", text, other),
+            }
+        }
+    }
+
+}